@@ -0,0 +1,3 @@
+-- Minimum number of votes an option needs to count as passed (0 = no threshold)
+ALTER TABLE polls
+ADD COLUMN per_option_threshold INTEGER NOT NULL DEFAULT 0 CHECK (per_option_threshold >= 0);
@@ -0,0 +1,6 @@
+-- Votes without a resolvable IP are all stored as 'unknown'; they must not
+-- collide with each other on the per-poll uniqueness check.
+DROP INDEX idx_votes_poll_ip;
+
+CREATE UNIQUE INDEX idx_votes_poll_ip ON votes(poll_id, voter_ip)
+WHERE voter_ip <> 'unknown';
@@ -0,0 +1,3 @@
+-- SHA-256 of the optional voter contact; the raw address is never stored
+ALTER TABLE votes
+ADD COLUMN contact_hash TEXT;
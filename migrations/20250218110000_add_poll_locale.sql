@@ -0,0 +1,3 @@
+-- Optional BCP-47 language tag, e.g. 'fr-FR'
+ALTER TABLE polls
+ADD COLUMN locale TEXT;
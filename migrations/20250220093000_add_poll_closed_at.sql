@@ -0,0 +1,3 @@
+-- Set when a poll is closed early by hand; NULL for polls that run to expires_at
+ALTER TABLE polls
+ADD COLUMN closed_at TIMESTAMPTZ;
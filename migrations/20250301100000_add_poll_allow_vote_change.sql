@@ -0,0 +1,3 @@
+-- Lets voters change or retract their vote until the poll closes; off keeps votes final
+ALTER TABLE polls
+ADD COLUMN allow_vote_change BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,9 @@
+-- Votes now store a keyed HMAC of the voter's IP instead of the raw address.
+-- The HMAC key isn't available here, so existing addresses are scrubbed with a
+-- plain SHA-256; they no longer match new digests, which only matters for polls
+-- still open during the upgrade. 'unknown' stays as the no-IP marker.
+ALTER TABLE votes RENAME COLUMN voter_ip TO voter_ip_hash;
+
+UPDATE votes
+SET voter_ip_hash = encode(sha256(convert_to(voter_ip_hash, 'UTF8')), 'hex')
+WHERE voter_ip_hash <> 'unknown';
@@ -0,0 +1,10 @@
+-- Price of each option's Binance symbol when the poll opened, for polls created
+-- with capture_prices. price is Binance's decimal string, NULL if the option
+-- isn't a known symbol.
+CREATE TABLE poll_option_prices (
+    poll_id UUID NOT NULL REFERENCES polls(id) ON DELETE CASCADE,
+    option_index INTEGER NOT NULL,
+    price TEXT,
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (poll_id, option_index)
+);
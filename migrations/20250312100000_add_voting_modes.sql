@@ -0,0 +1,19 @@
+-- How a poll's votes are counted: one choice, any number of approvals, or a
+-- preference ranking tallied by instant runoff
+CREATE TYPE voting_mode AS ENUM ('single', 'approval', 'ranked');
+
+ALTER TABLE polls
+ADD COLUMN voting_mode voting_mode NOT NULL DEFAULT 'single';
+
+-- A ballot is stored as one row per selected option. rank is the option's
+-- position on the ballot: always 1 for single-choice votes, the preference
+-- order for ranked votes and the listing order for approvals.
+ALTER TABLE votes
+ADD COLUMN rank INTEGER NOT NULL DEFAULT 1 CHECK (rank >= 1);
+
+-- Every ballot has exactly one rank-1 row, so uniqueness on those rows still
+-- allows a single ballot per voter
+DROP INDEX idx_votes_poll_ip;
+
+CREATE UNIQUE INDEX idx_votes_poll_ip ON votes(poll_id, voter_ip_hash)
+WHERE voter_ip_hash <> 'unknown' AND rank = 1;
@@ -0,0 +1,5 @@
+-- Optional name of whoever created the poll, shown as its author
+ALTER TABLE polls
+ADD COLUMN created_by TEXT;
+
+CREATE INDEX idx_polls_created_by ON polls(created_by);
@@ -0,0 +1,7 @@
+-- Last price fetched from Binance per symbol, served as a stale fallback
+-- while Binance is unreachable
+CREATE TABLE price_cache (
+    symbol TEXT PRIMARY KEY,
+    price TEXT NOT NULL,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
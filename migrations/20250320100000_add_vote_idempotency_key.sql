@@ -0,0 +1,4 @@
+-- Idempotency-Key sent with a vote, so a retried submission can be answered
+-- like the original. Deleted with the vote, and so with its poll.
+ALTER TABLE votes
+ADD COLUMN idempotency_key TEXT;
@@ -0,0 +1,3 @@
+-- A key identifies one ballot per poll, whichever address a retry comes from
+CREATE UNIQUE INDEX idx_votes_poll_idempotency_key ON votes(poll_id, idempotency_key)
+WHERE idempotency_key IS NOT NULL AND rank = 1;
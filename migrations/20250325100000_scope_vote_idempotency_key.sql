@@ -0,0 +1,3 @@
+-- A key only replays the ballot of the voter who sent it; the per-voter
+-- uniqueness of rank-1 rows (idx_votes_poll_ip) already covers the lookup
+DROP INDEX idx_votes_poll_idempotency_key;
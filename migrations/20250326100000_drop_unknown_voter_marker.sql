@@ -0,0 +1,11 @@
+-- Votes now need a resolvable client IP, so no new 'unknown' rows appear.
+-- Existing ones get the per-vote marker of legacy votes, which keeps them
+-- distinct voters, and the dedup index covers every ballot again.
+UPDATE votes
+SET voter_ip_hash = 'legacy-' || id
+WHERE voter_ip_hash = 'unknown';
+
+DROP INDEX idx_votes_poll_ip;
+
+CREATE UNIQUE INDEX idx_votes_poll_ip ON votes(poll_id, voter_ip_hash)
+WHERE rank = 1;
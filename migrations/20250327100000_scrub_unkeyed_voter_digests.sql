@@ -0,0 +1,8 @@
+-- 20250305100000 replaced the addresses of earlier votes with an unkeyed
+-- SHA-256, which brute force reverses for IPv4. Votes cast before it ran get
+-- a per-vote 'legacy-<id>' marker instead, so each counts as its own voter;
+-- votes cast since carry keyed HMACs and are left alone.
+UPDATE votes
+SET voter_ip_hash = 'legacy-' || id
+WHERE voter_ip_hash NOT LIKE 'legacy-%'
+    AND created_at < (SELECT installed_on FROM _sqlx_migrations WHERE version = 20250305100000);
@@ -0,0 +1,17 @@
+-- Counts changes to a poll's votes, giving results stream events ids that
+-- only grow, so a reconnecting client can resume with Last-Event-ID.
+ALTER TABLE polls
+ADD COLUMN results_version BIGINT NOT NULL DEFAULT 0;
+
+CREATE FUNCTION bump_poll_results_version() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE polls
+    SET results_version = results_version + 1
+    WHERE id = COALESCE(NEW.poll_id, OLD.poll_id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER votes_bump_poll_results_version
+AFTER INSERT OR UPDATE OR DELETE ON votes
+FOR EACH ROW EXECUTE FUNCTION bump_poll_results_version();
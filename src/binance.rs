@@ -0,0 +1,263 @@
+// src/binance.rs
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::metrics::metrics;
+
+/// Longest Binance may take to accept a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest a single request may take overall; Binance occasionally hangs.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the set of listed symbols is reused before Binance is asked again.
+const SYMBOL_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Symbols seen in the last successful price fetch, and when it happened.
+type SymbolCache = Option<(Instant, Arc<HashSet<String>>)>;
+
+/// How long the outcome of a reachability check is reused by readiness probes.
+const REACHABILITY_CACHE_TTL: Duration = Duration::from_secs(30);
+/// Longest a reachability check waits for Binance.
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Outcome of the last reachability check, and when it happened.
+type ReachabilityCache = Option<(Instant, bool)>;
+
+/// Binance error code for an unknown symbol.
+const INVALID_SYMBOL: i64 = -1121;
+
+/// Attempts per request before a transient failure is given up on.
+const MAX_ATTEMPTS: u32 = 3;
+/// Pause before the first retry, doubled before each further one.
+const RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Failure of a Binance request.
+#[derive(Debug)]
+pub enum BinanceError {
+    /// The requested symbol (or one of several) isn't listed on Binance.
+    SymbolNotFound(String),
+    /// Binance rejected the request with one of its error codes.
+    Api { code: i64, message: String },
+    /// Binance couldn't be reached or answered with something unexpected,
+    /// on the last of `attempts` tries.
+    Network { error: reqwest::Error, attempts: u32 },
+}
+
+impl fmt::Display for BinanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SymbolNotFound(symbol) => write!(f, "unknown symbol: {}", symbol),
+            Self::Api { code, message } => write!(f, "Binance error {}: {}", code, message),
+            Self::Network { error, attempts: 1 } => write!(f, "{}", error),
+            Self::Network { error, attempts } => write!(f, "{} (after {} attempts)", error, attempts),
+        }
+    }
+}
+
+impl std::error::Error for BinanceError {}
+
+impl From<reqwest::Error> for BinanceError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Network { error, attempts: 1 }
+    }
+}
+
+/// Error payload Binance sends with `400` responses.
+#[derive(Debug, Deserialize)]
+struct ApiErrorBody {
+    code: i64,
+    msg: String,
+}
+
+/// Latest price of one Binance trading pair. Prices stay strings, as
+/// Binance sends them, so no precision is lost.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TickerPrice {
+    pub symbol: String,
+    pub price: String,
+}
+
+/// Client for Binance's public market data API (`BINANCE_API_URL`).
+#[derive(Clone)]
+pub struct BinanceClient {
+    client: reqwest::Client,
+    base_url: String,
+    symbols: Arc<RwLock<SymbolCache>>,
+    reachability: Arc<RwLock<ReachabilityCache>>,
+}
+
+impl BinanceClient {
+    pub fn new(base_url: String) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to build Binance HTTP client");
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            symbols: Arc::new(RwLock::new(None)),
+            reachability: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Fetches the latest price of every symbol listed on Binance.
+    pub async fn fetch_crypto_prices(&self) -> Result<Vec<TickerPrice>, BinanceError> {
+        let prices: Vec<TickerPrice> = self.request_ticker(&[]).await?;
+
+        let symbols = prices.iter().map(|p| p.symbol.clone()).collect();
+        *self.symbols.write().expect("symbol cache lock poisoned") = Some((Instant::now(), Arc::new(symbols)));
+        Ok(prices)
+    }
+
+    /// Fetches the latest price of a single symbol such as `BTCUSDT`.
+    pub async fn fetch_price(&self, symbol: &str) -> Result<TickerPrice, BinanceError> {
+        self.request_ticker(&[("symbol", symbol.to_string())]).await
+    }
+
+    /// Fetches the latest prices of several symbols in one request. Fails with
+    /// `SymbolNotFound` if any of them isn't listed.
+    pub async fn fetch_prices(&self, symbols: &[String]) -> Result<Vec<TickerPrice>, BinanceError> {
+        let list = serde_json::to_string(symbols).expect("symbol list serializes to JSON");
+        self.request_ticker(&[("symbols", list)]).await.map_err(|e| match e {
+            BinanceError::SymbolNotFound(_) => BinanceError::SymbolNotFound(format!("one of {}", symbols.join(", "))),
+            e => e,
+        })
+    }
+
+    /// Sends a ticker request, retrying timeouts, connection failures and
+    /// `5xx` answers with exponential backoff. Other failures, notably `4xx`,
+    /// are returned straight away.
+    async fn request_ticker<T: DeserializeOwned>(&self, query: &[(&str, String)]) -> Result<T, BinanceError> {
+        let mut attempts = 1;
+        let mut backoff = RETRY_BACKOFF;
+        let result = loop {
+            match self.send_ticker_request(query).await {
+                Err(BinanceError::Network { error, .. }) if attempts < MAX_ATTEMPTS && is_retryable(&error) => {
+                    warn!(
+                        "Binance request failed (attempt {} of {}), retrying in {:?}: {}",
+                        attempts, MAX_ATTEMPTS, backoff, error
+                    );
+                    tokio::time::sleep(backoff).await;
+                    attempts += 1;
+                    backoff *= 2;
+                }
+                Err(BinanceError::Network { error, .. }) => break Err(BinanceError::Network { error, attempts }),
+                result => break result,
+            }
+        };
+        let outcome = if result.is_ok() { "success" } else { "failure" };
+        metrics().binance_fetches.with_label_values(&[outcome]).inc();
+        result
+    }
+
+    async fn send_ticker_request<T: DeserializeOwned>(&self, query: &[(&str, String)]) -> Result<T, BinanceError> {
+        let response = self
+            .client
+            .get(format!("{}/api/v3/ticker/price", self.base_url))
+            .query(query)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::BAD_REQUEST {
+            let body: ApiErrorBody = response.json().await?;
+            if body.code == INVALID_SYMBOL {
+                let symbols = query.iter().map(|(_, value)| value.as_str()).collect::<Vec<_>>().join(",");
+                return Err(BinanceError::SymbolNotFound(symbols));
+            }
+            return Err(BinanceError::Api { code: body.code, message: body.msg });
+        }
+        Ok(response.error_for_status()?.json().await?)
+    }
+
+    /// Whether Binance answers a ping. The outcome is reused for
+    /// `REACHABILITY_CACHE_TTL`, so frequent probes don't hammer Binance.
+    pub async fn is_reachable(&self) -> bool {
+        if let Some((checked_at, reachable)) = *self.reachability.read().expect("reachability lock poisoned") {
+            if checked_at.elapsed() < REACHABILITY_CACHE_TTL {
+                return reachable;
+            }
+        }
+
+        let reachable = self
+            .client
+            .get(format!("{}/api/v3/ping", self.base_url))
+            .timeout(REACHABILITY_TIMEOUT)
+            .send()
+            .await
+            .is_ok_and(|response| response.status().is_success());
+        *self.reachability.write().expect("reachability lock poisoned") = Some((Instant::now(), reachable));
+        reachable
+    }
+
+    /// Returns the symbols listed on Binance, from cache when the last price
+    /// fetch is recent enough.
+    pub async fn known_symbols(&self) -> Result<Arc<HashSet<String>>, BinanceError> {
+        if let Some((fetched_at, symbols)) = &*self.symbols.read().expect("symbol cache lock poisoned") {
+            if fetched_at.elapsed() < SYMBOL_CACHE_TTL {
+                return Ok(symbols.clone());
+            }
+        }
+
+        self.fetch_crypto_prices().await?;
+        let cache = self.symbols.read().expect("symbol cache lock poisoned");
+        Ok(cache.as_ref().map(|(_, symbols)| symbols.clone()).unwrap_or_default())
+    }
+}
+
+/// Whether a failed request may succeed when sent again.
+fn is_retryable(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.status().is_some_and(|status| status.is_server_error())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode as HttpStatus, routing::get, Router};
+    use tokio::net::TcpListener;
+
+    /// Serves `/api/v3/ping` answering `status` and returns its base URL.
+    async fn mock_binance(status: HttpStatus) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/api/v3/ping", get(move || async move { status }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn reachable_when_ping_succeeds() {
+        let client = BinanceClient::new(mock_binance(HttpStatus::OK).await);
+        assert!(client.is_reachable().await);
+    }
+
+    #[tokio::test]
+    async fn unreachable_when_ping_fails() {
+        let client = BinanceClient::new(mock_binance(HttpStatus::SERVICE_UNAVAILABLE).await);
+        assert!(!client.is_reachable().await);
+    }
+
+    #[tokio::test]
+    async fn unreachable_when_nothing_listens() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        assert!(!BinanceClient::new(format!("http://{}", addr)).is_reachable().await);
+    }
+
+    #[tokio::test]
+    async fn reachability_is_cached() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let client = BinanceClient::new(format!("http://{}", addr));
+        *client.reachability.write().unwrap() = Some((Instant::now(), true));
+        assert!(client.is_reachable().await);
+    }
+}
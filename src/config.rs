@@ -0,0 +1,257 @@
+// src/config.rs
+use chrono::Duration;
+use std::env;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+use std::time::Duration as StdDuration;
+
+use crate::features::Feature;
+
+/// Runtime settings read from the environment once at startup.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Address the server listens on (`HOST`, default `0.0.0.0`, and `PORT`, default 3000).
+    pub bind_addr: SocketAddr,
+    /// Extra time after `expires_at` during which votes are still accepted
+    /// (`VOTE_GRACE_PERIOD_SECONDS`, default 0). The deadline is checked
+    /// against the moment the server received the vote, not any client clock.
+    pub vote_grace_period: Duration,
+    /// How long before a poll stops taking votes its results stream sends an
+    /// `expiring_soon` event (`POLL_EXPIRING_SOON_SECONDS`, default 60, 0 disables).
+    pub poll_expiring_soon_lead: Duration,
+    /// Overall deadline for a request (`REQUEST_TIMEOUT_SECS`, default 30).
+    /// Also applied as the Postgres `statement_timeout`, so a query left
+    /// behind by a timed-out request cannot keep holding its connection.
+    pub request_timeout: StdDuration,
+    /// Upper bound on stored polls (`MAX_POLLS_RETAINED`, unset = unlimited).
+    /// Creating a poll beyond the cap deletes the oldest expired or closed polls.
+    pub max_polls_retained: Option<i64>,
+    /// Duration of polls created without an expiry
+    /// (`POLL_DEFAULT_DURATION_MINUTES`, default 1440).
+    pub poll_default_duration_minutes: i64,
+    /// Longest a poll may run, checked for both `expires_in_minutes` and
+    /// `expires_at` (`POLL_MAX_DURATION_MINUTES`, unset = unlimited).
+    pub poll_max_duration_minutes: Option<i64>,
+    /// Open polls one `created_by` may have at a time, counting clones
+    /// (`MAX_ACTIVE_POLLS_PER_AUTHOR`, unset = unlimited). Excess gets `409`.
+    pub max_active_polls_per_author: Option<i64>,
+    /// How long expired polls and their votes are kept before the background
+    /// purge deletes them (`POLL_RETENTION_DAYS`, default 30).
+    pub poll_retention: Duration,
+    /// Time between purge runs (`POLL_PURGE_INTERVAL_MINUTES`, default 60).
+    pub poll_purge_interval: StdDuration,
+    /// Bucket the purge uploads each poll's final results and anonymized votes
+    /// to before deleting it (`ARCHIVE_S3_*`). Polls are purged without an
+    /// archive when `ARCHIVE_S3_BUCKET` is unset.
+    pub archive_storage: Option<ArchiveStorage>,
+    /// Key for per-voter option shuffling and its order tokens
+    /// (`OPTION_ORDER_SECRET`). Shuffling is disabled when unset.
+    pub option_order_secret: Option<String>,
+    /// Process-wide request ceiling in requests per second
+    /// (`GLOBAL_RATE_LIMIT_RPS`, unset = unlimited). Excess gets `503`.
+    pub global_rate_limit_rps: Option<f64>,
+    /// Requests allowed above the steady rate in a burst
+    /// (`GLOBAL_RATE_LIMIT_BURST`, defaults to one second's worth).
+    pub global_rate_limit_burst: Option<f64>,
+    /// Per-IP vote rate in requests per second (`VOTE_RATE_LIMIT_RPS`, default 1,
+    /// 0 disables) and burst (`VOTE_RATE_LIMIT_BURST`, default 5). Excess gets `429`.
+    pub vote_rate_limit: (f64, f64),
+    /// Per-IP poll creation rate and burst (`POLL_CREATE_RATE_LIMIT_RPS`,
+    /// default 0.1, 0 disables, and `POLL_CREATE_RATE_LIMIT_BURST`, default 3).
+    pub poll_create_rate_limit: (f64, f64),
+    /// Where vote confirmations for voters who left a contact are posted
+    /// (`VOTE_NOTIFY_WEBHOOK_URL`). No confirmations are sent when unset.
+    pub vote_notify_webhook_url: Option<String>,
+    /// Features that start switched off (`DISABLED_FEATURES`, comma-separated
+    /// names such as `prices,streaming`). Admins can toggle them at runtime.
+    pub disabled_features: Vec<Feature>,
+    /// Reject request bodies carrying fields the endpoint doesn't know
+    /// (`STRICT_JSON`, default false). Off keeps lenient parsing for old clients.
+    pub strict_json: bool,
+    /// Oldest `X-Client-Version` still served (`MIN_CLIENT_VERSION`, dotted
+    /// numbers such as `2.4.0`); older clients get `426 Upgrade Required`.
+    pub min_client_version: Option<Vec<u64>>,
+    /// Where outdated clients are told to upgrade (`CLIENT_UPGRADE_URL`).
+    pub client_upgrade_url: Option<String>,
+    /// Whether requests without `X-Client-Version` are rejected too
+    /// (`CLIENT_VERSION_REQUIRED`, default false).
+    pub client_version_required: bool,
+    /// Reject votes with a missing or blank `User-Agent` (`REQUIRE_USER_AGENT`, default false).
+    pub require_user_agent: bool,
+    /// Lowercased substrings of `User-Agent`s whose votes are rejected
+    /// (`USER_AGENT_BLOCKLIST`, comma-separated, e.g. `curl,python-requests`).
+    pub user_agent_blocklist: Vec<String>,
+    /// Name the pool's connections report in `pg_stat_activity`
+    /// (`DATABASE_APPLICATION_NAME`, default `crypto_poll`).
+    pub database_application_name: String,
+    /// Most connections the pool opens (`DATABASE_MAX_CONNECTIONS`, default 5).
+    pub database_max_connections: u32,
+    /// Connections kept open even when idle (`DATABASE_MIN_CONNECTIONS`, default 0).
+    pub database_min_connections: u32,
+    /// How long a request waits for a free connection
+    /// (`DATABASE_ACQUIRE_TIMEOUT_SECS`, default 30).
+    pub database_acquire_timeout: StdDuration,
+    /// How long an unused connection is kept open
+    /// (`DATABASE_IDLE_TIMEOUT_SECS`, default 600).
+    pub database_idle_timeout: StdDuration,
+    /// Connection attempts at startup before giving up (`DATABASE_CONNECT_ATTEMPTS`,
+    /// default 5), for databases that come up after the server.
+    pub database_connect_attempts: u32,
+    /// Pause between startup connection attempts (`DATABASE_CONNECT_RETRY_DELAY_SECS`, default 2).
+    pub database_connect_retry_delay: StdDuration,
+    /// Bearer token required to create, clone and close polls (`ADMIN_TOKEN`).
+    /// When unset those endpoints refuse every request.
+    pub admin_token: Option<String>,
+    /// Proxies in front of the server that append to `X-Forwarded-For`
+    /// (`TRUSTED_PROXY_HOPS`, default 1). The client IP is taken that many
+    /// entries from the right; 0 ignores proxy headers and uses the socket peer.
+    pub trusted_proxy_hops: usize,
+    /// Key for the HMAC stored in place of voter IPs (`VOTER_IP_SALT`, required).
+    /// Changing it stops new votes from matching earlier ones in open polls.
+    pub voter_ip_salt: String,
+    /// Largest request body accepted, in bytes (`MAX_BODY_BYTES`, default 65536).
+    /// Larger bodies get `413` before any of them is parsed.
+    pub max_body_bytes: usize,
+    /// Base URL of the Binance API backing `/api/coins`
+    /// (`BINANCE_API_URL`, default `https://api.binance.com`).
+    pub binance_api_url: String,
+    /// Serve the last successfully fetched prices, flagged `stale`, when Binance
+    /// is unreachable (`PRICE_FALLBACK`, default true).
+    pub price_fallback: bool,
+    /// Base URL of Binance's websocket streams feeding `/api/prices/ws`
+    /// (`BINANCE_WS_URL`, default `wss://stream.binance.com:9443`).
+    pub binance_ws_url: String,
+    /// Price updates buffered per `/api/prices/ws` client (`PRICE_STREAM_BUFFER`,
+    /// default 256). A client further behind is resynced with a snapshot.
+    pub price_stream_buffer: usize,
+    /// Lead over the runner-up, in percentage points, up to which the winner
+    /// endpoint reports `is_close` (`CLOSE_RACE_THRESHOLD_PERCENT`, default 5).
+    pub close_race_threshold_percent: f64,
+    /// Report Binance reachability in `/health` (`READINESS_CHECKS_BINANCE`, default false).
+    pub readiness_checks_binance: bool,
+    /// Report not ready while that check finds Binance unreachable
+    /// (`READINESS_REQUIRES_BINANCE`, default false).
+    pub readiness_requires_binance: bool,
+}
+
+/// S3-compatible bucket for poll archives.
+#[derive(Debug, Clone)]
+pub struct ArchiveStorage {
+    /// Base URL of the service (`ARCHIVE_S3_ENDPOINT`), e.g. `https://s3.eu-west-1.amazonaws.com`.
+    pub endpoint: String,
+    /// `ARCHIVE_S3_BUCKET`.
+    pub bucket: String,
+    /// Region requests are signed for (`ARCHIVE_S3_REGION`, default `us-east-1`).
+    pub region: String,
+    /// `ARCHIVE_S3_ACCESS_KEY_ID`.
+    pub access_key_id: String,
+    /// `ARCHIVE_S3_SECRET_ACCESS_KEY`.
+    pub secret_access_key: String,
+}
+
+impl Config {
+    /// Loads the configuration, panicking on malformed values so that
+    /// misconfiguration is caught at startup rather than per request.
+    pub fn from_env() -> Self {
+        Self::from_vars(|key| env::var(key).ok())
+    }
+
+    /// Like [`Config::from_env`], reading each variable through `var`.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let var = &var;
+        Self {
+            bind_addr: SocketAddr::new(
+                env_or(var, "HOST", IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+                env_or(var, "PORT", 3000),
+            ),
+            vote_grace_period: Duration::seconds(env_or(var, "VOTE_GRACE_PERIOD_SECONDS", 0)),
+            poll_expiring_soon_lead: Duration::seconds(env_or(var, "POLL_EXPIRING_SOON_SECONDS", 60)),
+            request_timeout: StdDuration::from_secs(env_or(var, "REQUEST_TIMEOUT_SECS", 30)),
+            max_polls_retained: env_opt(var, "MAX_POLLS_RETAINED"),
+            poll_default_duration_minutes: env_or(var, "POLL_DEFAULT_DURATION_MINUTES", 1440),
+            poll_max_duration_minutes: env_opt(var, "POLL_MAX_DURATION_MINUTES"),
+            max_active_polls_per_author: env_opt(var, "MAX_ACTIVE_POLLS_PER_AUTHOR"),
+            poll_retention: Duration::days(env_or(var, "POLL_RETENTION_DAYS", 30)),
+            poll_purge_interval: StdDuration::from_secs(env_or(var, "POLL_PURGE_INTERVAL_MINUTES", 60u64) * 60),
+            archive_storage: env_opt::<String>(var, "ARCHIVE_S3_BUCKET").map(|bucket| ArchiveStorage {
+                endpoint: var("ARCHIVE_S3_ENDPOINT").expect("ARCHIVE_S3_ENDPOINT must be set with ARCHIVE_S3_BUCKET"),
+                bucket,
+                region: env_or(var, "ARCHIVE_S3_REGION", "us-east-1".to_string()),
+                access_key_id: var("ARCHIVE_S3_ACCESS_KEY_ID")
+                    .expect("ARCHIVE_S3_ACCESS_KEY_ID must be set with ARCHIVE_S3_BUCKET"),
+                secret_access_key: var("ARCHIVE_S3_SECRET_ACCESS_KEY")
+                    .expect("ARCHIVE_S3_SECRET_ACCESS_KEY must be set with ARCHIVE_S3_BUCKET"),
+            }),
+            option_order_secret: env_opt(var, "OPTION_ORDER_SECRET"),
+            global_rate_limit_rps: env_opt(var, "GLOBAL_RATE_LIMIT_RPS"),
+            global_rate_limit_burst: env_opt(var, "GLOBAL_RATE_LIMIT_BURST"),
+            vote_rate_limit: (env_or(var, "VOTE_RATE_LIMIT_RPS", 1.0), env_or(var, "VOTE_RATE_LIMIT_BURST", 5.0)),
+            poll_create_rate_limit: (
+                env_or(var, "POLL_CREATE_RATE_LIMIT_RPS", 0.1),
+                env_or(var, "POLL_CREATE_RATE_LIMIT_BURST", 3.0),
+            ),
+            vote_notify_webhook_url: env_opt(var, "VOTE_NOTIFY_WEBHOOK_URL"),
+            disabled_features: env_opt::<String>(var, "DISABLED_FEATURES")
+                .map(|list| {
+                    list.split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .map(|name| name.parse().unwrap_or_else(|e| panic!("Invalid DISABLED_FEATURES value: {}", e)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            strict_json: env_or(var, "STRICT_JSON", false),
+            min_client_version: env_opt::<String>(var, "MIN_CLIENT_VERSION").map(|v| {
+                parse_version(&v).unwrap_or_else(|| panic!("Invalid MIN_CLIENT_VERSION value: {}", v))
+            }),
+            client_upgrade_url: env_opt(var, "CLIENT_UPGRADE_URL"),
+            client_version_required: env_or(var, "CLIENT_VERSION_REQUIRED", false),
+            require_user_agent: env_or(var, "REQUIRE_USER_AGENT", false),
+            user_agent_blocklist: env_opt::<String>(var, "USER_AGENT_BLOCKLIST")
+                .map(|list| {
+                    list.split(',')
+                        .map(|entry| entry.trim().to_lowercase())
+                        .filter(|entry| !entry.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            database_application_name: env_or(var, "DATABASE_APPLICATION_NAME", "crypto_poll".to_string()),
+            database_max_connections: env_or(var, "DATABASE_MAX_CONNECTIONS", 5),
+            database_min_connections: env_or(var, "DATABASE_MIN_CONNECTIONS", 0),
+            database_acquire_timeout: StdDuration::from_secs(env_or(var, "DATABASE_ACQUIRE_TIMEOUT_SECS", 30)),
+            database_idle_timeout: StdDuration::from_secs(env_or(var, "DATABASE_IDLE_TIMEOUT_SECS", 600)),
+            database_connect_attempts: env_or(var, "DATABASE_CONNECT_ATTEMPTS", 5u32).max(1),
+            database_connect_retry_delay: StdDuration::from_secs(env_or(var, "DATABASE_CONNECT_RETRY_DELAY_SECS", 2)),
+            admin_token: env_opt::<String>(var, "ADMIN_TOKEN").filter(|t| !t.is_empty()),
+            trusted_proxy_hops: env_or(var, "TRUSTED_PROXY_HOPS", 1),
+            voter_ip_salt: var("VOTER_IP_SALT").expect("VOTER_IP_SALT must be set"),
+            max_body_bytes: env_or(var, "MAX_BODY_BYTES", 64 * 1024),
+            binance_api_url: env_or(var, "BINANCE_API_URL", "https://api.binance.com".to_string()),
+            price_fallback: env_or(var, "PRICE_FALLBACK", true),
+            binance_ws_url: env_or(var, "BINANCE_WS_URL", "wss://stream.binance.com:9443".to_string()),
+            price_stream_buffer: env_or(var, "PRICE_STREAM_BUFFER", 256usize).max(1),
+            close_race_threshold_percent: env_or(var, "CLOSE_RACE_THRESHOLD_PERCENT", 5.0),
+            readiness_checks_binance: env_or(var, "READINESS_CHECKS_BINANCE", false),
+            readiness_requires_binance: env_or(var, "READINESS_REQUIRES_BINANCE", false),
+        }
+    }
+}
+
+/// Parses a dotted version such as `1.10.2` into its numeric components.
+pub fn parse_version(version: &str) -> Option<Vec<u64>> {
+    version.trim().split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Parses the variable `key`, or returns `default` when it is unset.
+fn env_or<T: FromStr>(var: &dyn Fn(&str) -> Option<String>, key: &str, default: T) -> T {
+    env_opt(var, key).unwrap_or(default)
+}
+
+/// Parses the variable `key` if it is set.
+fn env_opt<T: FromStr>(var: &dyn Fn(&str) -> Option<String>, key: &str) -> Option<T> {
+    var(key).map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid {} value: {}", key, value))
+    })
+}
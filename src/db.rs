@@ -1,14 +1,117 @@
 // src/db.rs
-use sqlx::postgres::PgPoolOptions;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use sqlx::{Pool, Postgres};
 use std::env;
+use std::time::Duration;
+use tracing::{info, warn};
 
-/// Creates a connection pool for the PostgreSQL database.
-pub async fn create_pool() -> Result<Pool<Postgres>, sqlx::Error> {
+use crate::config::Config;
+
+/// Creates the connection pool serving requests.
+///
+/// Every connection gets a `statement_timeout` matching the request deadline,
+/// so the server aborts queries whose request has already been given up on
+/// and the connection returns to the pool, and identifies itself with the
+/// configured `application_name`. Connecting is retried so the server can
+/// start before the database is ready; the last error is returned once the
+/// attempts run out.
+pub async fn create_pool(config: &Config) -> Result<Pool<Postgres>, sqlx::Error> {
+    info!(
+        "Database pool: max_connections={}, min_connections={}, acquire_timeout={:?}, idle_timeout={:?}",
+        config.database_max_connections,
+        config.database_min_connections,
+        config.database_acquire_timeout,
+        config.database_idle_timeout
+    );
+
+    let pool_options = PgPoolOptions::new()
+        .max_connections(config.database_max_connections)
+        .min_connections(config.database_min_connections)
+        .acquire_timeout(config.database_acquire_timeout)
+        .idle_timeout(config.database_idle_timeout);
+    connect(config, pool_options, Some(config.request_timeout)).await
+}
+
+/// Creates a single-connection pool for migrations and the purge task, which
+/// run outside any request and may take longer than the request deadline.
+pub async fn create_maintenance_pool(config: &Config) -> Result<Pool<Postgres>, sqlx::Error> {
+    let pool_options = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(config.database_acquire_timeout)
+        .idle_timeout(config.database_idle_timeout);
+    connect(config, pool_options, None).await
+}
+
+async fn connect(
+    config: &Config,
+    pool_options: PgPoolOptions,
+    statement_timeout: Option<Duration>,
+) -> Result<Pool<Postgres>, sqlx::Error> {
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let options = connect_options(database_url.parse()?, &config.database_application_name, statement_timeout);
+
+    let attempts = config.database_connect_attempts;
+    let mut attempt = 1;
+    loop {
+        match pool_options.clone().connect_with(options.clone()).await {
+            Err(e) if attempt < attempts => {
+                warn!(
+                    "Database connection attempt {}/{} failed: {}; retrying in {:?}",
+                    attempt, attempts, e, config.database_connect_retry_delay
+                );
+                tokio::time::sleep(config.database_connect_retry_delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Connection settings of every pooled connection. The name can be checked on
+/// a running server with
+/// `SELECT DISTINCT application_name FROM pg_stat_activity WHERE usename = current_user;`.
+fn connect_options(
+    options: PgConnectOptions,
+    application_name: &str,
+    statement_timeout: Option<Duration>,
+) -> PgConnectOptions {
+    let options = options.application_name(application_name);
+    match statement_timeout {
+        Some(timeout) => options.options([("statement_timeout", timeout.as_millis().to_string())]),
+        None => options,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connections_carry_the_application_name() {
+        let url = "postgres://user@localhost/polls".parse().unwrap();
+        let options = connect_options(url, "crypto_poll", Some(Duration::from_secs(30)));
+        assert_eq!(options.get_application_name(), Some("crypto_poll"));
+    }
+
+    #[test]
+    fn configured_name_overrides_the_url() {
+        let url = "postgres://user@localhost/polls?application_name=psql".parse().unwrap();
+        let options = connect_options(url, "poll-worker", Some(Duration::from_secs(30)));
+        assert_eq!(options.get_application_name(), Some("poll-worker"));
+    }
+
+    #[sqlx::test]
+    async fn cancelled_query_frees_its_connection(pool_options: PgPoolOptions, options: PgConnectOptions) {
+        let options = connect_options(options, "crypto_poll", Some(Duration::from_millis(200)));
+        let pool = pool_options.max_connections(1).connect_with(options).await.unwrap();
+
+        // The request gives up long before the query would finish
+        let slow = sqlx::query("SELECT pg_sleep(30)").execute(&pool);
+        assert!(tokio::time::timeout(Duration::from_millis(50), slow).await.is_err());
 
-    PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&database_url)
-        .await
-}
\ No newline at end of file
+        // The only connection is usable again once the server aborts the query
+        let next = sqlx::query_scalar::<_, i32>("SELECT 1").fetch_one(&pool);
+        let value = tokio::time::timeout(Duration::from_secs(5), next).await.expect("connection was freed");
+        assert_eq!(value.unwrap(), 1);
+    }
+}
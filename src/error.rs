@@ -1,27 +1,144 @@
 // src/error.rs
 use axum::{
-    http::StatusCode,
+    extract::rejection::JsonRejection,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
+    Json,
 };
+use serde::Serialize;
+use utoipa::ToSchema;
 
+/// Failure of an API request, answered as `{"error": <code>, "message": <text>}`.
+/// `error` is a stable machine-readable code, `message` is for humans.
 #[derive(Debug)]
-pub struct AppError(anyhow::Error);
+pub enum ApiError {
+    /// The request is malformed or fails validation.
+    Validation(String),
+    /// The request is well-formed but semantically invalid.
+    Unprocessable(String),
+    /// The JSON body could not be read; keeps the status chosen by axum.
+    InvalidBody(StatusCode, String),
+    /// The body exceeds `MAX_BODY_BYTES`.
+    PayloadTooLarge,
+    PollNotFound,
+    NoActivePoll,
+    NotFound(String),
+    /// No route matches the request path.
+    RouteNotFound,
+    /// The path exists but not for the request method.
+    MethodNotAllowed,
+    /// The endpoint belongs to a feature an operator switched off.
+    FeatureDisabled(&'static str),
+    PollExpired,
+    AlreadyVoted,
+    /// The author already has the given maximum of open polls.
+    TooManyActivePolls(i64),
+    /// The poll does not let voters change or retract their vote.
+    VoteChangeNotAllowed,
+    /// An admin endpoint was called without a bearer token.
+    Unauthorized,
+    /// An admin endpoint was called with a wrong token, or admin access is disabled.
+    Forbidden,
+    /// The client exceeded a rate limit and may retry after the given delay.
+    RateLimited(std::time::Duration),
+    /// The client is older than `MIN_CLIENT_VERSION`; the message says how to upgrade.
+    UpgradeRequired(String),
+    /// The server is shedding load and may be retried after the given delay.
+    Overloaded(std::time::Duration),
+    /// An upstream service such as Binance failed or is unreachable.
+    Upstream(String),
+    /// A database query failed; details are logged where it happened.
+    Database(String),
+}
+
+/// JSON body of every error response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    /// Stable machine-readable code, e.g. `poll_not_found`.
+    pub error: &'static str,
+    pub message: String,
+}
 
-impl IntoResponse for AppError {
+impl ApiError {
+    fn parts(self) -> (StatusCode, &'static str, String) {
+        match self {
+            Self::Validation(message) => (StatusCode::BAD_REQUEST, "validation_error", message),
+            Self::Unprocessable(message) => (StatusCode::UNPROCESSABLE_ENTITY, "unprocessable", message),
+            Self::InvalidBody(status, message) => (status, "invalid_body", message),
+            Self::PayloadTooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+                "Request body is too large".to_string(),
+            ),
+            Self::PollNotFound => (StatusCode::NOT_FOUND, "poll_not_found", "Poll not found".to_string()),
+            Self::NoActivePoll => (StatusCode::NOT_FOUND, "no_active_poll", "No active poll".to_string()),
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, "not_found", message),
+            Self::RouteNotFound => (StatusCode::NOT_FOUND, "route_not_found", "Route not found".to_string()),
+            Self::MethodNotAllowed => (
+                StatusCode::METHOD_NOT_ALLOWED,
+                "method_not_allowed",
+                "Method not allowed".to_string(),
+            ),
+            Self::FeatureDisabled(feature) => (
+                StatusCode::NOT_FOUND,
+                "feature_disabled",
+                format!("The {} feature is disabled", feature),
+            ),
+            Self::PollExpired => (StatusCode::BAD_REQUEST, "poll_expired", "Poll has expired".to_string()),
+            Self::AlreadyVoted => (StatusCode::CONFLICT, "already_voted", "Already voted".to_string()),
+            Self::TooManyActivePolls(limit) => (
+                StatusCode::CONFLICT,
+                "too_many_active_polls",
+                format!("An author may have at most {} open polls", limit),
+            ),
+            Self::VoteChangeNotAllowed => (
+                StatusCode::FORBIDDEN,
+                "vote_change_not_allowed",
+                "This poll does not allow changing votes".to_string(),
+            ),
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, "unauthorized", "Missing admin token".to_string()),
+            Self::Forbidden => (StatusCode::FORBIDDEN, "forbidden", "Invalid admin token".to_string()),
+            Self::RateLimited(_) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate_limited",
+                "Too many requests, retry later".to_string(),
+            ),
+            Self::UpgradeRequired(message) => (StatusCode::UPGRADE_REQUIRED, "upgrade_required", message),
+            Self::Overloaded(_) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "overloaded",
+                "Server is over capacity, retry later".to_string(),
+            ),
+            Self::Upstream(message) => (StatusCode::BAD_GATEWAY, "upstream_error", message),
+            Self::Database(message) => (StatusCode::INTERNAL_SERVER_ERROR, "database_error", message),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Something went wrong: {}", self.0),
-        )
-            .into_response()
+        let extra_header = match &self {
+            Self::Unauthorized => Some((header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"))),
+            Self::RateLimited(retry_after) | Self::Overloaded(retry_after) => {
+                let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+                Some((header::RETRY_AFTER, HeaderValue::from(seconds)))
+            }
+            _ => None,
+        };
+        let (status, code, message) = self.parts();
+        let mut response = (status, Json(ErrorBody { error: code, message })).into_response();
+        if let Some((name, value)) = extra_header {
+            response.headers_mut().insert(name, value);
+        }
+        response
     }
 }
 
-impl<E> From<E> for AppError
-where
-    E: Into<anyhow::Error>,
-{
-    fn from(err: E) -> Self {
-        Self(err.into())
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            return Self::PayloadTooLarge;
+        }
+        Self::InvalidBody(rejection.status(), rejection.body_text())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,199 @@
+// src/extract.rs
+use axum::{
+    extract::{ConnectInfo, FromRef, FromRequest, FromRequestParts, OptionalFromRequest, Request},
+    http::{header, request::Parts, HeaderMap},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::error::ApiError;
+
+/// JSON body extractor that, with `STRICT_JSON` enabled, rejects fields the
+/// target type does not know about with a `400` naming them. Otherwise it
+/// behaves exactly like `Json` and ignores unexpected fields.
+pub struct StrictJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for StrictJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+    Arc<Config>: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !Arc::<Config>::from_ref(state).strict_json {
+            let Json(value) = Json::<T>::from_request(req, state).await?;
+            return Ok(Self(value));
+        }
+
+        // Parse generically first so content-type and syntax errors match `Json`
+        let Json(raw) = Json::<serde_json::Value>::from_request(req, state).await?;
+
+        let mut unknown = Vec::new();
+        let value = serde_ignored::deserialize(raw, |path| unknown.push(path.to_string()))
+            .map_err(|e| ApiError::Unprocessable(format!("Failed to deserialize the JSON body: {}", e)))?;
+
+        if !unknown.is_empty() {
+            return Err(ApiError::Validation(format!("Unknown fields: {}", unknown.join(", "))));
+        }
+        Ok(Self(value))
+    }
+}
+
+/// `Option<StrictJson<T>>` is `None` for a request without a body, i.e. one
+/// with no `Content-Type` or an empty `Content-Length`.
+impl<T, S> OptionalFromRequest<S> for StrictJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+    Arc<Config>: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        let headers = req.headers();
+        let empty = !headers.contains_key(header::CONTENT_TYPE)
+            || headers.get(header::CONTENT_LENGTH).is_some_and(|length| length == "0");
+        if empty {
+            return Ok(None);
+        }
+        <Self as FromRequest<S>>::from_request(req, state).await.map(Some)
+    }
+}
+
+/// Guard for admin endpoints: requires `Authorization: Bearer <ADMIN_TOKEN>`,
+/// answering `401` without the header and `403` for a wrong token.
+pub struct AdminAuth;
+
+impl<S> FromRequestParts<S> for AdminAuth
+where
+    S: Send + Sync,
+    Arc<Config>: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or(ApiError::Unauthorized)?;
+
+        let config = Arc::<Config>::from_ref(state);
+        match &config.admin_token {
+            Some(expected) if constant_time_eq(token.trim(), expected) => Ok(Self),
+            _ => Err(ApiError::Forbidden),
+        }
+    }
+}
+
+/// Compares the SHA-256 digests of both strings without short-circuiting,
+/// so timing reveals neither the matching prefix nor the token length.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (Sha256::digest(a.as_bytes()), Sha256::digest(b.as_bytes()));
+    a.iter().zip(b.iter()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// The client's IP address, or `None` when it can't be determined. Behind
+/// `TRUSTED_PROXY_HOPS` proxies it is read from `X-Forwarded-For` (or
+/// `X-Real-IP` when that is missing); otherwise it is the socket peer.
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+    Arc<Config>: FromRef<S>,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let hops = Arc::<Config>::from_ref(state).trusted_proxy_hops;
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        Ok(Self(resolve_client_ip(&parts.headers, hops, peer)))
+    }
+}
+
+/// Each trusted proxy appends the address it received the request from, so
+/// entries left of the last `hops` are client-controlled and ignored. With a
+/// shorter chain every entry came from a trusted proxy and the leftmost wins.
+fn resolve_client_ip(headers: &HeaderMap, hops: usize, peer: Option<IpAddr>) -> Option<IpAddr> {
+    if hops == 0 {
+        return peer;
+    }
+
+    let header_value = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let forwarded = header_value("x-forwarded-for").and_then(|chain| {
+        let entries: Vec<&str> = chain.split(',').map(str::trim).collect();
+        let entry = entries.iter().rev().nth(hops - 1).or(entries.first())?;
+        entry.parse().ok()
+    });
+
+    forwarded
+        .or_else(|| header_value("x-real-ip").and_then(|ip| ip.trim().parse().ok()))
+        .or(peer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in pairs {
+            map.insert(name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    fn ip(addr: &str) -> Option<IpAddr> {
+        Some(addr.parse().unwrap())
+    }
+
+    #[test]
+    fn no_trusted_hops_uses_the_peer() {
+        let forwarded = headers(&[("x-forwarded-for", "1.2.3.4")]);
+        assert_eq!(resolve_client_ip(&forwarded, 0, ip("10.0.0.1")), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn client_controlled_entries_are_ignored() {
+        let forwarded = headers(&[("x-forwarded-for", "6.6.6.6, 1.2.3.4, 10.0.0.2")]);
+        assert_eq!(resolve_client_ip(&forwarded, 1, None), ip("10.0.0.2"));
+        assert_eq!(resolve_client_ip(&forwarded, 2, None), ip("1.2.3.4"));
+    }
+
+    #[test]
+    fn short_chain_uses_the_leftmost_entry() {
+        let forwarded = headers(&[("x-forwarded-for", "1.2.3.4")]);
+        assert_eq!(resolve_client_ip(&forwarded, 3, None), ip("1.2.3.4"));
+    }
+
+    #[test]
+    fn falls_back_to_real_ip_then_peer() {
+        let real_ip = headers(&[("x-real-ip", " 1.2.3.4 ")]);
+        assert_eq!(resolve_client_ip(&real_ip, 1, ip("10.0.0.1")), ip("1.2.3.4"));
+        let garbage = headers(&[("x-forwarded-for", "not-an-ip")]);
+        assert_eq!(resolve_client_ip(&garbage, 1, ip("10.0.0.1")), ip("10.0.0.1"));
+        assert_eq!(resolve_client_ip(&HeaderMap::new(), 1, None), None);
+    }
+
+    #[test]
+    fn constant_time_eq_compares_whole_strings() {
+        assert!(constant_time_eq("token", "token"));
+        assert!(!constant_time_eq("token", "tokem"));
+        assert!(!constant_time_eq("token", "token-longer"));
+        assert!(!constant_time_eq("", "token"));
+        assert!(constant_time_eq("", ""));
+    }
+}
@@ -0,0 +1,109 @@
+// src/features.rs
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::FeatureStates;
+
+/// Optional parts of the API that operators can switch off while running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// `/api/coins` and `/api/prices/ws`.
+    Prices,
+    /// `/api/polls/{id}/results/stream`.
+    Streaming,
+    /// Vote confirmations posted to `VOTE_NOTIFY_WEBHOOK_URL`.
+    Webhooks,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 3] = [Feature::Prices, Feature::Streaming, Feature::Webhooks];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::Prices => "prices",
+            Feature::Streaming => "streaming",
+            Feature::Webhooks => "webhooks",
+        }
+    }
+}
+
+impl FromStr for Feature {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Feature::ALL
+            .into_iter()
+            .find(|feature| feature.name() == name)
+            .ok_or_else(|| format!("Unknown feature: {}", name))
+    }
+}
+
+/// Runtime on/off switch per [`Feature`], shared by every handler. Starts
+/// from `DISABLED_FEATURES` and changes through the admin endpoint; changes
+/// are not persisted across restarts.
+#[derive(Debug, Clone)]
+pub struct FeatureFlags {
+    enabled: Arc<[AtomicBool; 3]>,
+}
+
+impl FeatureFlags {
+    /// Flags with every feature on except `disabled`.
+    pub fn new(disabled: &[Feature]) -> Self {
+        let flags = Self { enabled: Arc::new(Default::default()) };
+        for feature in Feature::ALL {
+            flags.set(feature, !disabled.contains(&feature));
+        }
+        flags
+    }
+
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.enabled[feature as usize].load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, feature: Feature, enabled: bool) {
+        self.enabled[feature as usize].store(enabled, Ordering::Relaxed);
+    }
+
+    /// Fails with `404` while `feature` is off, so a disabled endpoint looks absent.
+    pub fn require(&self, feature: Feature) -> Result<(), ApiError> {
+        if self.is_enabled(feature) {
+            Ok(())
+        } else {
+            Err(ApiError::FeatureDisabled(feature.name()))
+        }
+    }
+
+    pub fn states(&self) -> FeatureStates {
+        FeatureStates {
+            prices: self.is_enabled(Feature::Prices),
+            streaming: self.is_enabled(Feature::Streaming),
+            webhooks: self.is_enabled(Feature::Webhooks),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_disabled_features_start_off() {
+        let flags = FeatureFlags::new(&[Feature::Streaming]);
+        assert_eq!(flags.states(), FeatureStates { prices: true, streaming: false, webhooks: true });
+
+        flags.set(Feature::Streaming, true);
+        flags.set(Feature::Prices, false);
+        assert!(flags.require(Feature::Streaming).is_ok());
+        assert!(flags.require(Feature::Prices).is_err());
+    }
+
+    #[test]
+    fn features_parse_from_their_names() {
+        for feature in Feature::ALL {
+            assert_eq!(feature.name().parse(), Ok(feature));
+        }
+        assert!("binance".parse::<Feature>().is_err());
+    }
+}
@@ -1,183 +1,3429 @@
 // src/handlers.rs
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     Json,
 };
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Utc, Duration};
+use futures_util::stream::{self, Stream};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::net::IpAddr;
+use std::sync::{Arc, LazyLock};
 use uuid::Uuid;
-use tracing::error; // For logging errors
-use crate::models::{Poll, CreatePoll, VoteRequest};
+use tracing::{error, info, warn}; // For logging errors
+use crate::binance::{BinanceClient, BinanceError, TickerPrice};
+use crate::config::Config;
+use crate::error::{ApiError, ErrorBody};
+use crate::extract::{AdminAuth, ClientIp, StrictJson};
+use crate::features::{Feature, FeatureFlags};
+use crate::metrics::metrics;
+use crate::models::{
+    AuthorResultsPage, ChangeVote, ClonePoll, CoinPrice, CoinsParams, CompactResults, FeatureStates, FeatureToggle, HealthStatus, OptionCount, OptionResult, PageParams, Poll, CreatePoll, ListPollsParams, PollBundle, PollResults, PollStats, PollDetails, PollWinner, PollWithExpiry, PollVelocity, PollsPage, PreviewVote, PriceStreamParams, ReplayPage,
+    ReplayParams, ReplayVote, ResultsStreamParams, RunoffRound, ServerTime, ShuffledOptions, TimeseriesBucket, VelocityParams, Vote, VoteRequest,
+    UpstreamStatus, VotesPage, VotesParams, VotingMode, WinnerMargin,
+};
+use crate::notify::VoteConfirmation;
+use crate::price_cache;
+use crate::price_stream::{Delivery, PriceStream};
+use crate::rate_limit::{KeyedLimiter, LeakyBucket, RateLimits};
+use crate::runoff;
+use crate::shuffle;
+use crate::state::AppState;
 
 /// Creates a new poll in the database.
+#[utoipa::path(
+    post,
+    path = "/api/polls",
+    tag = "polls",
+    request_body = CreatePoll,
+    responses(
+        (status = 200, description = "The created poll", body = Poll),
+        (status = 400, description = "Invalid title, options, threshold or author, unknown Binance symbol, or expiry beyond the maximum", body = ErrorBody),
+        (status = 401, description = "Missing admin token", body = ErrorBody),
+        (status = 403, description = "Invalid admin token", body = ErrorBody),
+        (status = 409, description = "The author has too many open polls", body = ErrorBody),
+        (status = 422, description = "Invalid locale", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+        (status = 502, description = "Binance unavailable while validating symbols", body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
 pub async fn create_poll(
+    _admin: AdminAuth,
     State(pool): State<PgPool>,
-    Json(payload): Json<CreatePoll>,
-) -> Result<Json<Poll>, (StatusCode, String)> {
-    let mut tx = pool.begin().await.map_err(|e| {
-        error!("Failed to start transaction: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Database transaction failed".to_string())
+    State(config): State<Arc<Config>>,
+    State(binance): State<BinanceClient>,
+    State(rate_limits): State<Arc<RateLimits>>,
+    ClientIp(client_ip): ClientIp,
+    StrictJson(payload): StrictJson<CreatePoll>,
+) -> Result<Json<Poll>, ApiError> {
+    throttle(rate_limits.poll_creation.as_ref(), client_ip)?;
+    validate_title(&payload.title)?;
+    validate_options(&payload.options)?;
+    let expires_at = resolve_expiry(&config, payload.expires_in_minutes, payload.expires_at)?;
+    if payload.per_option_threshold < 0 {
+        return Err(ApiError::Validation("per_option_threshold must not be negative".to_string()));
+    }
+    if payload.locale.as_deref().is_some_and(|l| !is_valid_language_tag(l)) {
+        return Err(ApiError::Unprocessable("locale must be a BCP-47 language tag".to_string()));
+    }
+    let created_by = payload.created_by.as_deref().map(str::trim);
+    if let Some(created_by) = created_by {
+        validate_created_by(created_by)?;
+    }
+
+    if payload.validate_symbols {
+        validate_symbols(&binance, &payload.options).await?;
+    }
+
+    let option_prices = if payload.capture_prices {
+        Some(starting_prices(&binance, &payload.options).await)
+    } else {
+        None
+    };
+
+    let settings = PollSettings {
+        per_option_threshold: payload.per_option_threshold,
+        locale: payload.locale.as_deref(),
+        allow_vote_change: payload.allow_vote_change,
+        voting_mode: payload.voting_mode,
+        created_by,
+        option_prices,
+    };
+    let poll = insert_poll(&pool, &config, &payload.title, &payload.options, expires_at, settings).await?;
+
+    Ok(Json(poll))
+}
+
+/// Requires every option to be a symbol listed on Binance, ignoring case and
+/// surrounding whitespace.
+async fn validate_symbols(binance: &BinanceClient, options: &[String]) -> Result<(), ApiError> {
+    let symbols = binance.known_symbols().await.map_err(|e| {
+        error!("Failed to fetch symbols from Binance: {}", e);
+        ApiError::Upstream("Failed to fetch symbols from Binance".to_string())
     })?;
 
-    // Delete existing votes and polls to ensure only one poll exists at a time
-    if let Err(e) = sqlx::query!("DELETE FROM votes").execute(&mut *tx).await {
-        error!("Failed to delete votes: {}", e);
-        return Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete votes".to_string()));
+    let unknown: Vec<&str> = options
+        .iter()
+        .map(|o| o.trim())
+        .filter(|o| !symbols.contains(&o.to_uppercase()))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(ApiError::Validation(format!("Unknown symbols: {}", unknown.join(", "))));
     }
+    Ok(())
+}
+
+/// Looks up the current Binance price of each option, read as a symbol such as
+/// `BTCUSDT`. Unknown symbols get `None`, as do all options if Binance is down.
+async fn starting_prices(binance: &BinanceClient, options: &[String]) -> Vec<Option<String>> {
+    let prices = match binance.fetch_crypto_prices().await {
+        Ok(prices) => prices,
+        Err(e) => {
+            warn!("Failed to fetch starting prices from Binance: {}", e);
+            return vec![None; options.len()];
+        }
+    };
 
-    if let Err(e) = sqlx::query!("DELETE FROM polls").execute(&mut *tx).await {
-        error!("Failed to delete polls: {}", e);
-        return Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete polls".to_string()));
+    options
+        .iter()
+        .map(|option| {
+            let symbol = option.trim().to_uppercase();
+            prices.iter().find(|p| p.symbol == symbol).map(|p| p.price.clone())
+        })
+        .collect()
+}
+
+/// Creates a new poll from an existing one: same title, options and settings,
+/// a fresh expiry and no votes. The title gets a " (copy)" suffix unless overridden,
+/// and the expiry defaults to the source poll's original duration, capped at
+/// `POLL_MAX_DURATION_MINUTES`. The body may be omitted to take every default.
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/clone",
+    tag = "polls",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    request_body = ClonePoll,
+    responses(
+        (status = 200, description = "The new poll", body = Poll),
+        (status = 400, description = "Invalid request", body = ErrorBody),
+        (status = 401, description = "Missing admin token", body = ErrorBody),
+        (status = 403, description = "Invalid admin token", body = ErrorBody),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+        (status = 409, description = "The author has too many open polls", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn clone_poll(
+    _admin: AdminAuth,
+    State(state): State<AppState>,
+    Path(poll_id): Path<Uuid>,
+    ClientIp(client_ip): ClientIp,
+    payload: Option<StrictJson<ClonePoll>>,
+) -> Result<Json<Poll>, ApiError> {
+    throttle(state.rate_limits.poll_creation.as_ref(), client_ip)?;
+    let source = fetch_poll(&state.pool, poll_id).await?;
+    let payload = payload.map(|StrictJson(payload)| payload).unwrap_or_default();
+    Ok(Json(duplicate_poll(&state, source, payload).await?))
+}
+
+/// Clones the most recently created poll, like `POST /api/polls/{id}/clone`.
+#[utoipa::path(
+    post,
+    path = "/api/polls/current/clone",
+    tag = "polls",
+    request_body = ClonePoll,
+    responses(
+        (status = 200, description = "The new poll", body = Poll),
+        (status = 400, description = "Invalid request", body = ErrorBody),
+        (status = 401, description = "Missing admin token", body = ErrorBody),
+        (status = 403, description = "Invalid admin token", body = ErrorBody),
+        (status = 404, description = "No active poll", body = ErrorBody),
+        (status = 409, description = "The author has too many open polls", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn clone_current_poll(
+    _admin: AdminAuth,
+    State(state): State<AppState>,
+    ClientIp(client_ip): ClientIp,
+    payload: Option<StrictJson<ClonePoll>>,
+) -> Result<Json<Poll>, ApiError> {
+    throttle(state.rate_limits.poll_creation.as_ref(), client_ip)?;
+    let source = fetch_latest_poll(&state.pool)
+        .await?
+        .ok_or(ApiError::NoActivePoll)?;
+    let payload = payload.map(|StrictJson(payload)| payload).unwrap_or_default();
+    Ok(Json(duplicate_poll(&state, source, payload).await?))
+}
+
+/// Inserts a copy of `source` without its votes, applying the overrides of `payload`.
+/// Without an override the clone runs as long as the source did, capped at
+/// `POLL_MAX_DURATION_MINUTES`. Starting prices describe the moment a poll
+/// opened, so a clone of a poll that captured them captures its own.
+async fn duplicate_poll(state: &AppState, source: Poll, payload: ClonePoll) -> Result<Poll, ApiError> {
+    let AppState { pool, config, binance, .. } = state;
+    if let Some(minutes) = payload.expires_in_minutes {
+        validate_duration(config, minutes)?;
     }
 
-    // Insert the new poll
+    // The suffix can push a title that was valid over the length limit
+    let title = payload.title.unwrap_or_else(|| format!("{} (copy)", source.title));
+    validate_title(&title)?;
+    let duration = match payload.expires_in_minutes {
+        Some(minutes) => Duration::minutes(minutes),
+        None => {
+            let duration = source.expires_at - source.created_at;
+            config.poll_max_duration_minutes.map_or(duration, |max| duration.min(Duration::minutes(max)))
+        }
+    };
+
+    let option_prices = if has_option_prices(pool, source.id).await? {
+        Some(starting_prices(binance, &source.options).await)
+    } else {
+        None
+    };
+
+    let settings = PollSettings {
+        per_option_threshold: source.per_option_threshold,
+        locale: source.locale.as_deref(),
+        allow_vote_change: source.allow_vote_change,
+        voting_mode: source.voting_mode,
+        created_by: source.created_by.as_deref(),
+        option_prices,
+    };
+    insert_poll(pool, config, &title, &source.options, Utc::now() + duration, settings).await
+}
+
+/// Whether starting prices were captured when `poll_id` was created.
+async fn has_option_prices(pool: &PgPool, poll_id: Uuid) -> Result<bool, ApiError> {
+    sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM poll_option_prices WHERE poll_id = $1) as "exists!""#,
+        poll_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to look up option prices of {}: {}", poll_id, e);
+        ApiError::Database("Failed to create poll".to_string())
+    })
+}
+
+/// Closes a poll before its expiry; closing an already closed poll keeps the original `closed_at`.
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/close",
+    tag = "polls",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    responses(
+        (status = 200, description = "The closed poll", body = Poll),
+        (status = 401, description = "Missing admin token", body = ErrorBody),
+        (status = 403, description = "Invalid admin token", body = ErrorBody),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn close_poll(
+    _admin: AdminAuth,
+    State(pool): State<PgPool>,
+    Path(poll_id): Path<Uuid>,
+) -> Result<Json<Poll>, ApiError> {
     let poll = sqlx::query_as!(
         Poll,
         r#"
-        INSERT INTO polls (title, options, expires_at)
-        VALUES ($1, $2, $3)
-        RETURNING *
+        UPDATE polls
+        SET closed_at = COALESCE(closed_at, NOW())
+        WHERE id = $1
+        RETURNING id, title, options, expires_at, created_at, per_option_threshold, locale, closed_at, allow_vote_change,
+            voting_mode as "voting_mode: VotingMode", created_by
         "#,
-        payload.title,
-        &payload.options,
-        Utc::now() + Duration::minutes(payload.expires_in_minutes)
+        poll_id
     )
-    .fetch_one(&mut *tx)
+    .fetch_optional(&pool)
     .await
     .map_err(|e| {
-        error!("Failed to insert poll: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to create poll".to_string())
+        error!("Failed to close poll {}: {}", poll_id, e);
+        ApiError::Database("Failed to close poll".to_string())
+    })?
+    .ok_or(ApiError::PollNotFound)?;
+
+    Ok(Json(poll))
+}
+
+/// Lists polls newest first, a page at a time (`?limit=` up to 100, default 20,
+/// `?offset=`), optionally only those of one author (`?created_by=`) or
+/// language (`?locale=`).
+#[utoipa::path(
+    get,
+    path = "/api/polls",
+    tag = "polls",
+    params(ListPollsParams),
+    responses(
+        (status = 200, description = "A page of polls, newest first", body = PollsPage),
+        (status = 422, description = "Invalid locale", body = ErrorBody),
+    ),
+)]
+pub async fn list_polls(
+    State(pool): State<PgPool>,
+    Query(params): Query<ListPollsParams>,
+) -> Result<Json<PollsPage>, ApiError> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+    if params.locale.as_deref().is_some_and(|l| !is_valid_language_tag(l)) {
+        return Err(ApiError::Unprocessable("locale must be a BCP-47 language tag".to_string()));
+    }
+
+    // Break ties on id so polls created in the same instant keep their page
+    let polls = sqlx::query_as!(
+        Poll,
+        r#"
+        SELECT id, title, options, expires_at, created_at, per_option_threshold, locale, closed_at, allow_vote_change,
+            voting_mode as "voting_mode: VotingMode", created_by
+        FROM polls
+        WHERE ($3::TEXT IS NULL OR created_by = $3)
+            AND ($4::TEXT IS NULL OR LOWER(locale) = LOWER($4))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $1 OFFSET $2
+        "#,
+        limit,
+        offset,
+        params.created_by.as_deref(),
+        params.locale.as_deref()
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to list polls: {}", e);
+        ApiError::Database("Failed to list polls".to_string())
     })?;
 
-    tx.commit().await.map_err(|e| {
-        error!("Failed to commit transaction: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Transaction commit failed".to_string())
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM polls
+        WHERE ($1::TEXT IS NULL OR created_by = $1)
+            AND ($2::TEXT IS NULL OR LOWER(locale) = LOWER($2))
+        "#,
+        params.created_by.as_deref(),
+        params.locale.as_deref()
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to count polls: {}", e);
+        ApiError::Database("Failed to list polls".to_string())
     })?;
 
-    Ok(Json(poll))
+    Ok(Json(PollsPage { polls, total, limit, offset }))
 }
 
-/// Retrieves the most recent poll from the database.
-pub async fn get_current_poll(
+/// Lists the other polls of a poll's author, newest first, a page at a time
+/// (`?limit=` up to 100, default 20, `?offset=`). A poll without an author has
+/// no related polls.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/related",
+    tag = "polls",
+    params(("id" = Uuid, Path, description = "Poll id"), PageParams),
+    responses(
+        (status = 200, description = "A page of the author's other polls, newest first", body = PollsPage),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn list_related_polls(
     State(pool): State<PgPool>,
-) -> Result<Json<Option<Poll>>, (StatusCode, String)> {
-    let poll = sqlx::query_as!(
+    Path(poll_id): Path<Uuid>,
+    Query(params): Query<PageParams>,
+) -> Result<Json<PollsPage>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let Some(author) = poll.created_by.as_deref() else {
+        return Ok(Json(PollsPage { polls: Vec::new(), total: 0, limit, offset }));
+    };
+
+    let polls = sqlx::query_as!(
         Poll,
-        r#"SELECT * FROM polls ORDER BY created_at DESC LIMIT 1"#
+        r#"
+        SELECT id, title, options, expires_at, created_at, per_option_threshold, locale, closed_at, allow_vote_change,
+            voting_mode as "voting_mode: VotingMode", created_by
+        FROM polls
+        WHERE created_by = $1 AND id <> $2
+        ORDER BY created_at DESC, id DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        author,
+        poll.id,
+        limit,
+        offset
     )
-    .fetch_optional(&pool)
+    .fetch_all(&pool)
     .await
     .map_err(|e| {
-        error!("Failed to fetch current poll: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch current poll".to_string())
+        error!("Failed to list polls related to {}: {}", poll.id, e);
+        ApiError::Database("Failed to list polls".to_string())
+    })?;
+
+    let total = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!" FROM polls WHERE created_by = $1 AND id <> $2"#,
+        author,
+        poll.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to count polls related to {}: {}", poll.id, e);
+        ApiError::Database("Failed to list polls".to_string())
     })?;
 
+    Ok(Json(PollsPage { polls, total, limit, offset }))
+}
+
+/// Retrieves a poll by id. With `OPTION_ORDER_SECRET` set, the options are
+/// also given in a stable per-voter shuffled order, so that no option benefits
+/// from always being listed first; votes by position then carry the order token.
+/// `server_time` lets clients correct their countdown to `expires_at` for clock skew.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}",
+    tag = "polls",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    responses(
+        (status = 200, description = "The poll", body = PollDetails),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn get_poll(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    Path(poll_id): Path<Uuid>,
+    ClientIp(client_ip): ClientIp,
+) -> Result<Json<PollDetails>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+
+    let shuffled = config.option_order_secret.as_deref().map(|secret| {
+        let voter = client_ip.map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+        let order = shuffle::shuffled_order(secret, poll.id, &voter, poll.options.len());
+        ShuffledOptions {
+            options: order.iter().map(|&i| poll.options[i].clone()).collect(),
+            order_token: shuffle::sign_order(secret, poll.id, &order),
+        }
+    });
+
+    Ok(Json(PollDetails { poll, server_time: Utc::now(), shuffled }))
+}
+
+/// Retrieves the most recent poll from the database, with its expiry state
+/// so clients don't have to trust their own clock.
+#[utoipa::path(
+    get,
+    path = "/api/polls/current",
+    tag = "polls",
+    responses(
+        (status = 200, description = "The most recent poll, or `null` when there is none", body = Option<PollWithExpiry>),
+    ),
+)]
+pub async fn get_current_poll(
+    State(pool): State<PgPool>,
+) -> Result<Json<Option<PollWithExpiry>>, ApiError> {
+    let now = Utc::now();
+    let poll = fetch_latest_poll(&pool).await?.map(|poll| {
+        let is_expired = poll.closed_at.is_some() || now >= poll.expires_at;
+        let seconds_remaining = if is_expired { 0 } else { (poll.expires_at - now).num_seconds() };
+        PollWithExpiry { poll, is_expired, seconds_remaining }
+    });
     Ok(Json(poll))
 }
 
-/// Submits a vote for the current poll.
+/// Submits a vote for a poll, answering `204 No Content` once recorded. A
+/// retry by the same voter carrying the `Idempotency-Key` of a recorded vote
+/// gets `204` again.
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/vote",
+    tag = "votes",
+    params(
+        ("id" = Uuid, Path, description = "Poll id"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Makes retries of this vote succeed"),
+    ),
+    request_body = VoteRequest,
+    responses(
+        (status = 204, description = "Vote recorded"),
+        (status = 400, description = "Invalid option, expired poll, rejected User-Agent, unknown client IP or invalid Idempotency-Key", body = ErrorBody),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+        (status = 409, description = "Already voted", body = ErrorBody),
+        (status = 422, description = "Idempotency-Key reused for a different vote", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    ),
+)]
 pub async fn submit_vote(
-    State(pool): State<PgPool>,
+    State(state): State<AppState>,
+    Path(poll_id): Path<Uuid>,
+    ClientIp(client_ip): ClientIp,
+    headers: HeaderMap,
+    StrictJson(payload): StrictJson<VoteRequest>,
+) -> Result<StatusCode, ApiError> {
+    cast_vote(&state, &headers, client_ip, payload, Some(poll_id)).await
+}
+
+/// Submits a vote for the most recent poll, honouring `Idempotency-Key` likewise.
+#[utoipa::path(
+    post,
+    path = "/api/votes",
+    tag = "votes",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Makes retries of this vote succeed"),
+    ),
+    request_body = VoteRequest,
+    responses(
+        (status = 204, description = "Vote recorded"),
+        (status = 400, description = "Invalid option, expired poll, rejected User-Agent, unknown client IP or invalid Idempotency-Key", body = ErrorBody),
+        (status = 404, description = "No active poll", body = ErrorBody),
+        (status = 409, description = "Already voted", body = ErrorBody),
+        (status = 422, description = "Idempotency-Key reused for a different vote", body = ErrorBody),
+        (status = 429, description = "Rate limited", body = ErrorBody),
+    ),
+)]
+pub async fn submit_vote_current(
+    State(state): State<AppState>,
+    ClientIp(client_ip): ClientIp,
     headers: HeaderMap,
-    Json(payload): Json<VoteRequest>,
-) -> Result<Json<()>, (StatusCode, String)> {
-    let voter_ip = headers
-        .get("x-real-ip")
-        .or_else(|| headers.get("x-forwarded-for"))
+    StrictJson(payload): StrictJson<VoteRequest>,
+) -> Result<StatusCode, ApiError> {
+    cast_vote(&state, &headers, client_ip, payload, None).await
+}
+
+/// Records a vote for `poll_id`, or for the most recent poll when `None`.
+async fn cast_vote(
+    state: &AppState,
+    headers: &HeaderMap,
+    client_ip: Option<IpAddr>,
+    payload: VoteRequest,
+    poll_id: Option<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let AppState { pool, config, notifier, rate_limits, features, .. } = state;
+    throttle(rate_limits.votes.as_ref(), client_ip)?;
+
+    // Record when the vote reached us before any DB work delays the deadline check
+    let submitted_at = Utc::now();
+
+    // Light bot filter on the User-Agent
+    let user_agent = headers
+        .get(header::USER_AGENT)
         .and_then(|v| v.to_str().ok())
-        .unwrap_or("unknown")
-        .to_string();
+        .map(str::trim)
+        .unwrap_or("");
+    if config.require_user_agent && user_agent.is_empty() {
+        return Err(ApiError::Validation("Missing User-Agent".to_string()));
+    }
+    let user_agent = user_agent.to_lowercase();
+    if config.user_agent_blocklist.iter().any(|blocked| user_agent.contains(blocked.as_str())) {
+        return Err(ApiError::Validation("User-Agent not allowed".to_string()));
+    }
 
-    // Fetch the current poll
-    let current_poll = sqlx::query_as!(
-        Poll,
-        r#"SELECT * FROM polls ORDER BY created_at DESC LIMIT 1"#
+    let contact = payload.contact.as_deref().map(str::trim);
+    if contact.is_some_and(|c| !is_valid_email(c)) {
+        return Err(ApiError::Validation("Invalid contact email".to_string()));
+    }
+
+    let idempotency_key = idempotency_key(headers)?;
+
+    // Behind a proxy, a vote without forwarding headers can only be told apart
+    // by the proxy's own address, so every such voter would collide
+    if config.trusted_proxy_hops > 0 && !has_forwarding_headers(headers) {
+        warn_missing_proxy_headers();
+    }
+    let voter_ip_hash = voter_ip_hash(config, client_ip)?;
+
+    let poll = match poll_id {
+        Some(poll_id) => fetch_poll(pool, poll_id).await?,
+        None => fetch_latest_poll(pool)
+            .await?
+            .ok_or(ApiError::NoActivePoll)?,
+    };
+
+    // Check if the poll has expired, allowing for the configured grace period.
+    // A poll closed by hand stops taking votes immediately.
+    if poll.closed_at.is_some() || submitted_at > poll.expires_at + config.vote_grace_period {
+        metrics().votes_rejected.with_label_values(&["expired"]).inc();
+        return Err(ApiError::PollExpired);
+    }
+
+    let ballot = resolve_ballot(config, &poll, &payload)
+        .inspect_err(|_| metrics().votes_rejected.with_label_values(&["invalid_index"]).inc())?;
+
+    // A retry of a vote that went through gets the original answer
+    if let Some(key) = idempotency_key {
+        if is_repeated_vote(pool, poll.id, &voter_ip_hash, key, &ballot).await? {
+            return Ok(StatusCode::NO_CONTENT);
+        }
+    }
+
+    // Insert one row per choice, ranked in ballot order; the unique index on
+    // rank-1 rows rejects a second ballot atomically
+    let inserted = sqlx::query!(
+        r#"
+        INSERT INTO votes (poll_id, option_index, rank, voter_ip_hash, contact_hash, idempotency_key)
+        SELECT $1, option_index, rank::INTEGER, $3, $4, $5
+        FROM UNNEST($2::INTEGER[]) WITH ORDINALITY AS t(option_index, rank)
+        "#,
+        poll.id,
+        &ballot as &[i32],
+        voter_ip_hash,
+        contact.map(hash_contact),
+        idempotency_key
     )
-    .fetch_optional(&pool)
+    .execute(pool)
+    .await;
+
+    match inserted {
+        Ok(_) => {}
+        Err(e) if e.as_database_error().is_some_and(|db| db.is_unique_violation()) => {
+            // The original of a concurrent retry may have landed in between
+            if let Some(key) = idempotency_key {
+                if is_repeated_vote(pool, poll.id, &voter_ip_hash, key, &ballot).await? {
+                    return Ok(StatusCode::NO_CONTENT);
+                }
+            }
+            metrics().votes_rejected.with_label_values(&["already_voted"]).inc();
+            return Err(ApiError::AlreadyVoted);
+        }
+        Err(e) => {
+            error!("Failed to submit vote: {}", e);
+            return Err(ApiError::Database("Failed to submit vote".to_string()));
+        }
+    }
+
+    metrics().votes_submitted.inc();
+
+    // Confirm to voters who asked for it; the notifier delivers in the background
+    let notifier = notifier.as_ref().filter(|_| features.is_enabled(Feature::Webhooks));
+    if let (Some(notifier), Some(contact)) = (notifier, contact) {
+        notifier.notify(VoteConfirmation {
+            contact: contact.to_string(),
+            poll_id: poll.id,
+            poll_title: poll.title.clone(),
+            option: ballot.iter().map(|&i| poll.options[i as usize].as_str()).collect::<Vec<_>>().join(", "),
+        });
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Longest accepted `Idempotency-Key`.
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// Reads the optional `Idempotency-Key` header, which must be visible ASCII.
+fn idempotency_key(headers: &HeaderMap) -> Result<Option<&str>, ApiError> {
+    let Some(value) = headers.get("idempotency-key") else {
+        return Ok(None);
+    };
+    value
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|key| {
+            !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LEN && key.chars().all(|c| c.is_ascii_graphic())
+        })
+        .map(Some)
+        .ok_or(ApiError::Validation("Invalid Idempotency-Key".to_string()))
+}
+
+/// Whether the voter already submitted `ballot` on `poll_id` with `key`. The
+/// same key with another ballot is a client bug, answered `422`.
+async fn is_repeated_vote(
+    pool: &PgPool,
+    poll_id: Uuid,
+    voter_ip_hash: &str,
+    key: &str,
+    ballot: &[i32],
+) -> Result<bool, ApiError> {
+    let stored = sqlx::query_scalar!(
+        r#"
+        SELECT ARRAY_AGG(option_index ORDER BY rank) as "ballot!"
+        FROM votes
+        WHERE poll_id = $1 AND voter_ip_hash = $2
+        HAVING BOOL_OR(rank = 1 AND idempotency_key = $3)
+        "#,
+        poll_id,
+        voter_ip_hash,
+        key
+    )
+    .fetch_optional(pool)
     .await
     .map_err(|e| {
-        error!("Failed to fetch current poll: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch current poll".to_string())
-    })?
-    .ok_or((StatusCode::NOT_FOUND, "No active poll".to_string()))?;
+        error!("Failed to look up idempotency key: {}", e);
+        ApiError::Database("Failed to submit vote".to_string())
+    })?;
 
-    // Check if the poll has expired
-    if Utc::now() > current_poll.expires_at {
-        return Err((StatusCode::BAD_REQUEST, "Poll has expired".to_string()));
+    match stored {
+        Some(stored) if stored != ballot => Err(ApiError::Unprocessable(
+            "Idempotency-Key was already used for a different vote".to_string(),
+        )),
+        stored => Ok(stored.is_some()),
+    }
+}
+
+/// Changes the caller's vote on a poll that allows it, answering `204 No Content`.
+#[utoipa::path(
+    put,
+    path = "/api/polls/{id}/vote",
+    tag = "votes",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    request_body = ChangeVote,
+    responses(
+        (status = 204, description = "Vote changed"),
+        (status = 400, description = "Invalid option, expired poll or unknown client IP", body = ErrorBody),
+        (status = 403, description = "The poll does not allow changing votes", body = ErrorBody),
+        (status = 404, description = "Poll or vote not found", body = ErrorBody),
+        (status = 422, description = "The poll is not single-choice", body = ErrorBody),
+    ),
+)]
+pub async fn change_vote(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    State(rate_limits): State<Arc<RateLimits>>,
+    Path(poll_id): Path<Uuid>,
+    ClientIp(client_ip): ClientIp,
+    StrictJson(payload): StrictJson<ChangeVote>,
+) -> Result<StatusCode, ApiError> {
+    throttle(rate_limits.votes.as_ref(), client_ip)?;
+    let (poll, voter_ip_hash) = open_for_vote_change(&pool, &config, poll_id, client_ip).await?;
+    if poll.voting_mode != VotingMode::Single {
+        return Err(ApiError::Unprocessable(
+            "Only single-choice votes can be changed; retract the vote and cast it again".to_string(),
+        ));
     }
+    let order = verify_order_token(&config, &poll, payload.order_token.as_deref())?;
+    let option_index = resolve_option_index(&poll, payload.option_index, order.as_deref())?;
 
-    // Check if the user has already voted
-    let existing_vote = sqlx::query!(
-        r#"SELECT id FROM votes WHERE poll_id = $1 AND voter_ip = $2"#,
-        current_poll.id,
-        voter_ip
+    let updated = sqlx::query!(
+        r#"UPDATE votes SET option_index = $3 WHERE poll_id = $1 AND voter_ip_hash = $2"#,
+        poll.id,
+        voter_ip_hash,
+        option_index
     )
-    .fetch_optional(&pool)
+    .execute(&pool)
     .await
     .map_err(|e| {
-        error!("Failed to check for existing vote: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to check for existing vote".to_string())
+        error!("Failed to change vote: {}", e);
+        ApiError::Database("Failed to change vote".to_string())
     })?;
 
-    if existing_vote.is_some() {
-        return Err((StatusCode::BAD_REQUEST, "Already voted".to_string()));
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Vote not found".to_string()));
     }
+    Ok(StatusCode::NO_CONTENT)
+}
 
-    // Insert the vote
-    sqlx::query!(
-        r#"
-        INSERT INTO votes (poll_id, option_index, voter_ip)
-        VALUES ($1, $2, $3)
-        "#,
-        current_poll.id,
-        payload.option_index,
-        voter_ip
+/// Retracts the caller's vote on a poll that allows it, answering `204 No Content`.
+#[utoipa::path(
+    delete,
+    path = "/api/polls/{id}/vote",
+    tag = "votes",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    responses(
+        (status = 204, description = "Vote retracted"),
+        (status = 400, description = "Expired poll or unknown client IP", body = ErrorBody),
+        (status = 403, description = "The poll does not allow changing votes", body = ErrorBody),
+        (status = 404, description = "Poll or vote not found", body = ErrorBody),
+    ),
+)]
+pub async fn retract_vote(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    State(rate_limits): State<Arc<RateLimits>>,
+    Path(poll_id): Path<Uuid>,
+    ClientIp(client_ip): ClientIp,
+) -> Result<StatusCode, ApiError> {
+    throttle(rate_limits.votes.as_ref(), client_ip)?;
+    let (poll, voter_ip_hash) = open_for_vote_change(&pool, &config, poll_id, client_ip).await?;
+
+    let deleted = sqlx::query!(
+        r#"DELETE FROM votes WHERE poll_id = $1 AND voter_ip_hash = $2"#,
+        poll.id,
+        voter_ip_hash
     )
     .execute(&pool)
     .await
     .map_err(|e| {
-        error!("Failed to submit vote: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to submit vote".to_string())
+        error!("Failed to retract vote: {}", e);
+        ApiError::Database("Failed to retract vote".to_string())
     })?;
 
-    Ok(Json(()))
+    if deleted.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Vote not found".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Loads a poll whose votes may still be changed and identifies the caller.
+async fn open_for_vote_change(
+    pool: &PgPool,
+    config: &Config,
+    poll_id: Uuid,
+    client_ip: Option<IpAddr>,
+) -> Result<(Poll, String), ApiError> {
+    let poll = fetch_poll(pool, poll_id).await?;
+    if !poll.allow_vote_change {
+        return Err(ApiError::VoteChangeNotAllowed);
+    }
+    if poll.closed_at.is_some() || Utc::now() > poll.expires_at + config.vote_grace_period {
+        return Err(ApiError::PollExpired);
+    }
+
+    Ok((poll, voter_ip_hash(config, client_ip)?))
+}
+
+/// Turns the choices of a vote into the ballot to store: real option indices,
+/// most preferred first. Each voting mode takes its own field of the request;
+/// a single-choice vote may name its option by index or by label.
+fn resolve_ballot(config: &Config, poll: &Poll, payload: &VoteRequest) -> Result<Vec<i32>, ApiError> {
+    let fields = (&payload.option_index, &payload.option, &payload.approvals, &payload.ranking);
+    let choices = match (poll.voting_mode, fields) {
+        (VotingMode::Single, (Some(index), None, None, None)) => std::slice::from_ref(index),
+        (VotingMode::Single, (None, Some(label), None, None)) => return Ok(vec![option_by_label(poll, label)?]),
+        (VotingMode::Single, _) => {
+            return Err(ApiError::Validation("Give exactly one of `option_index` and `option`".to_string()));
+        }
+        (VotingMode::Approval, (None, None, Some(approvals), None)) => approvals.as_slice(),
+        (VotingMode::Ranked, (None, None, None, Some(ranking))) => ranking.as_slice(),
+        (mode, _) => {
+            let field = if mode == VotingMode::Approval { "approvals" } else { "ranking" };
+            return Err(ApiError::Validation(format!("This poll takes its choices in `{}` only", field)));
+        }
+    };
+    if choices.is_empty() {
+        return Err(ApiError::Validation("Choose at least one option".to_string()));
+    }
+
+    let order = verify_order_token(config, poll, payload.order_token.as_deref())?;
+    let ballot = choices
+        .iter()
+        .map(|&index| resolve_option_index(poll, index, order.as_deref()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut seen = std::collections::HashSet::new();
+    if !ballot.iter().all(|&index| seen.insert(index)) {
+        return Err(ApiError::Validation("Each option may be chosen only once".to_string()));
+    }
+    Ok(ballot)
+}
+
+/// Finds the option labelled `label`, ignoring case and surrounding whitespace
+/// as option uniqueness does.
+fn option_by_label(poll: &Poll, label: &str) -> Result<i32, ApiError> {
+    let label = label.trim().to_lowercase();
+    (0..)
+        .zip(&poll.options)
+        .find(|(_, option)| option.trim().to_lowercase() == label)
+        .map(|(index, _)| index)
+        .ok_or(ApiError::Validation("Unknown option".to_string()))
+}
+
+/// Reads the voter's shuffled option order from an `order_token`, if one was given.
+fn verify_order_token(
+    config: &Config,
+    poll: &Poll,
+    order_token: Option<&str>,
+) -> Result<Option<Vec<usize>>, ApiError> {
+    order_token
+        .map(|token| {
+            config
+                .option_order_secret
+                .as_deref()
+                .and_then(|secret| shuffle::verify_order(secret, poll.id, token))
+                .ok_or(ApiError::Validation("Invalid order token".to_string()))
+        })
+        .transpose()
+}
+
+/// Turns a submitted option index into a real index of `poll.options`,
+/// mapping it through the voter's shuffled `order` when there is one.
+fn resolve_option_index(poll: &Poll, option_index: i32, order: Option<&[usize]>) -> Result<i32, ApiError> {
+    let option_index = match order {
+        Some(order) => usize::try_from(option_index)
+            .ok()
+            .and_then(|position| order.get(position))
+            .and_then(|&index| i32::try_from(index).ok())
+            .ok_or(ApiError::Validation("Invalid option index".to_string()))?,
+        None => option_index,
+    };
+    if option_index < 0 || option_index >= poll.options.len() as i32 {
+        return Err(ApiError::Validation("Invalid option index".to_string()));
+    }
+    Ok(option_index)
 }
 
-/// Retrieves the results of the current poll.
+/// Retrieves the results of a poll.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/results",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    responses(
+        (status = 200, description = "Tally of the poll", body = PollResults),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
 pub async fn get_results(
     State(pool): State<PgPool>,
-) -> Result<Json<Vec<(i32, i64)>>, (StatusCode, String)> {
-    let current_poll = sqlx::query_as!(
+    Path(poll_id): Path<Uuid>,
+) -> Result<Json<PollResults>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    Ok(Json(fetch_results(&pool, &poll).await?))
+}
+
+/// Retrieves the results of the most recent poll.
+#[utoipa::path(
+    get,
+    path = "/api/results",
+    tag = "results",
+    responses(
+        (status = 200, description = "Tally of the most recent poll", body = PollResults),
+        (status = 404, description = "No active poll", body = ErrorBody),
+    ),
+)]
+pub async fn get_current_results(
+    State(pool): State<PgPool>,
+) -> Result<Json<PollResults>, ApiError> {
+    let poll = fetch_latest_poll(&pool)
+        .await?
+        .ok_or(ApiError::NoActivePoll)?;
+    Ok(Json(fetch_results(&pool, &poll).await?))
+}
+
+/// Retrieves the results of every poll of `author`, newest first, a page at a
+/// time (`?limit=` up to 100, default 20, `?offset=`). The votes of the whole
+/// page are counted in one grouped query, so ranked polls come without their
+/// runoff `rounds`. Only admins create polls, and so attribute them to
+/// authors, hence only admins read an author's results.
+#[utoipa::path(
+    get,
+    path = "/api/authors/{author}/results",
+    tag = "results",
+    params(("author" = String, Path, description = "Author as given in `created_by`"), PageParams),
+    responses(
+        (status = 200, description = "A page of the author's polls with their results", body = AuthorResultsPage),
+        (status = 401, description = "Missing admin token", body = ErrorBody),
+        (status = 403, description = "Invalid admin token", body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn get_author_results(
+    _admin: AdminAuth,
+    State(pool): State<PgPool>,
+    Path(author): Path<String>,
+    Query(params): Query<PageParams>,
+) -> Result<Json<AuthorResultsPage>, ApiError> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let polls = sqlx::query_as!(
         Poll,
-        r#"SELECT * FROM polls ORDER BY created_at DESC LIMIT 1"#
+        r#"
+        SELECT id, title, options, expires_at, created_at, per_option_threshold, locale, closed_at, allow_vote_change,
+            voting_mode as "voting_mode: VotingMode", created_by
+        FROM polls
+        WHERE created_by = $1
+        ORDER BY created_at DESC, id DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        author,
+        limit,
+        offset
     )
-    .fetch_optional(&pool)
+    .fetch_all(&pool)
     .await
     .map_err(|e| {
-        error!("Failed to fetch current poll: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch current poll".to_string())
-    })?
-    .ok_or((StatusCode::NOT_FOUND, "No active poll".to_string()))?;
+        error!("Failed to list polls of {}: {}", author, e);
+        ApiError::Database("Failed to fetch results".to_string())
+    })?;
 
-    let results = sqlx::query!(
+    let total = sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM polls WHERE created_by = $1"#, author)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to count polls of {}: {}", author, e);
+            ApiError::Database("Failed to fetch results".to_string())
+        })?;
+
+    // Each ballot has exactly one rank-1 row, so those rows count the ballots
+    let ids: Vec<Uuid> = polls.iter().map(|poll| poll.id).collect();
+    let rows = sqlx::query!(
         r#"
-        SELECT option_index, COUNT(*) as count
-        FROM votes
-        WHERE poll_id = $1
-        GROUP BY option_index
+        SELECT v.poll_id, v.option_index,
+            COUNT(*) FILTER (WHERE v.rank = 1 OR p.voting_mode = 'approval') as "count!",
+            COUNT(*) FILTER (WHERE v.rank = 1) as "ballots!"
+        FROM votes v
+        JOIN polls p ON p.id = v.poll_id
+        WHERE v.poll_id = ANY($1)
+        GROUP BY v.poll_id, v.option_index
         "#,
-        current_poll.id
+        &ids
     )
     .fetch_all(&pool)
     .await
     .map_err(|e| {
-        error!("Failed to fetch results: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch results".to_string())
-    })?
-    .into_iter()
-    .map(|r| (r.option_index, r.count.unwrap_or(0)))
-    .collect();
+        error!("Failed to fetch results of {}: {}", author, e);
+        ApiError::Database("Failed to fetch results".to_string())
+    })?;
+
+    let prices = sqlx::query!(
+        r#"SELECT poll_id, option_index, price FROM poll_option_prices WHERE poll_id = ANY($1)"#,
+        &ids
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch option prices of {}: {}", author, e);
+        ApiError::Database("Failed to fetch results".to_string())
+    })?;
+
+    let results = polls
+        .iter()
+        .map(|poll| {
+            let mut counts = vec![0; poll.options.len()];
+            let mut ballots = 0;
+            for row in rows.iter().filter(|row| row.poll_id == poll.id) {
+                ballots += row.ballots;
+                if let Some(count) = usize::try_from(row.option_index).ok().and_then(|i| counts.get_mut(i)) {
+                    *count = row.count;
+                }
+            }
+            let mut option_prices = vec![None; poll.options.len()];
+            for row in prices.iter().filter(|row| row.poll_id == poll.id) {
+                if let Some(price) = usize::try_from(row.option_index).ok().and_then(|i| option_prices.get_mut(i)) {
+                    *price = row.price.clone();
+                }
+            }
+            build_results(poll, &counts, ballots, &option_prices)
+        })
+        .collect();
+
+    Ok(Json(AuthorResultsPage { polls: results, total, limit, offset }))
+}
+
+/// Streams a poll's results as Server-Sent Events: a `results` event whenever
+/// the tally changes (checked every `?interval_secs=`), an `expiring_soon`
+/// event `POLL_EXPIRING_SOON_SECONDS` before the poll stops taking votes, then
+/// a final `closed` event with the last tally once it has. `results` events
+/// carry the poll's results version as id; a client reconnecting with
+/// `Last-Event-ID` only gets results newer than that version.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/results/stream",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id"), ResultsStreamParams),
+    responses(
+        (status = 200, description = "`results` events carrying `PollResults` with the results version as id, an `expiring_soon` event carrying `{\"closes_at\"}`, then a final `closed` event", content_type = "text/event-stream", body = String),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn stream_results(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    State(features): State<FeatureFlags>,
+    Path(poll_id): Path<Uuid>,
+    Query(params): Query<ResultsStreamParams>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    features.require(Feature::Streaming)?;
+    // Fail with a regular 404 before committing to a stream
+    fetch_poll(&pool, poll_id).await?;
+
+    let interval = std::time::Duration::from_secs(params.interval_secs.unwrap_or(2).clamp(1, 60));
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    // An id we can't read just means the client gets the current results again
+    let last_version = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|id| id.trim().parse().ok());
+    let state = ResultsStreamState { ticker, last_version, wake_at: None, warned: false, finished: false };
+    let events = stream::unfold(state, move |mut state| {
+        let pool = pool.clone();
+        let config = config.clone();
+        async move {
+            if state.finished {
+                return None;
+            }
+            loop {
+                // Wake up for the expiry events even when no tick is due
+                match state.wake_at {
+                    Some(at) => {
+                        let delay = (at - Utc::now()).to_std().unwrap_or_default();
+                        tokio::select! {
+                            _ = state.ticker.tick() => {}
+                            _ = tokio::time::sleep(delay) => {}
+                        }
+                    }
+                    None => {
+                        state.ticker.tick().await;
+                    }
+                }
+
+                let tally = async {
+                    let poll = fetch_poll(&pool, poll_id).await?;
+                    let version = fetch_results_version(&pool, poll_id).await?;
+                    Ok::<_, ApiError>((fetch_results(&pool, &poll).await?, version, poll))
+                };
+                let (results, version, poll) = match tally.await {
+                    Ok(tally) => tally,
+                    Err(_) => {
+                        state.finished = true;
+                        let event = Event::default().event("error").data("Failed to fetch results");
+                        return Some((Ok(event), state));
+                    }
+                };
+                let data = serde_json::to_string(&results).expect("PollResults serializes to JSON");
+
+                let now = Utc::now();
+                let closes_at = poll.expires_at + config.vote_grace_period;
+                if poll.closed_at.is_some() || now > closes_at {
+                    state.finished = true;
+                    let event = Event::default().event("closed").data(data);
+                    return Some((Ok(event), state));
+                }
+                let warn_at = closes_at - config.poll_expiring_soon_lead;
+                if !state.warned && config.poll_expiring_soon_lead > Duration::zero() && now >= warn_at {
+                    state.warned = true;
+                    // Check the tally again straight away rather than at the next tick
+                    state.wake_at = Some(now);
+                    let data = serde_json::json!({ "closes_at": closes_at }).to_string();
+                    let event = Event::default().event("expiring_soon").data(data);
+                    return Some((Ok(event), state));
+                }
+                let next = if state.warned { closes_at } else { warn_at };
+                // Strictly after closes_at, so the closing check fires on waking
+                state.wake_at = Some(next + Duration::milliseconds(1));
+                if state.last_version.is_none_or(|last| version > last) {
+                    state.last_version = Some(version);
+                    let event = Event::default().event("results").id(version.to_string()).data(data);
+                    return Some((Ok(event), state));
+                }
+            }
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Progress of one `stream_results` subscriber.
+struct ResultsStreamState {
+    ticker: tokio::time::Interval,
+    /// Results version of the last `results` event the client has, to send only newer ones.
+    last_version: Option<i64>,
+    /// When the next expiry event is due, if sooner than the next tick.
+    wake_at: Option<DateTime<Utc>>,
+    /// Whether `expiring_soon` was sent.
+    warned: bool,
+    /// Whether the final event was sent.
+    finished: bool,
+}
+
+/// Number of changes to the votes of `poll_id` so far.
+async fn fetch_results_version(pool: &PgPool, poll_id: Uuid) -> Result<i64, ApiError> {
+    sqlx::query_scalar!("SELECT results_version FROM polls WHERE id = $1", poll_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch results version: {}", e);
+            ApiError::Database("Failed to fetch results".to_string())
+        })?
+        .ok_or(ApiError::PollNotFound)
+}
+
+/// Projects a poll's results as if one more vote were cast for `option_index`,
+/// or approving only it in an approval poll. Nothing is persisted.
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/preview-vote",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    request_body = PreviewVote,
+    responses(
+        (status = 200, description = "Results as if the vote were cast", body = PollResults),
+        (status = 400, description = "Invalid option", body = ErrorBody),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+        (status = 422, description = "The poll is ranked", body = ErrorBody),
+    ),
+)]
+pub async fn preview_vote(
+    State(pool): State<PgPool>,
+    Path(poll_id): Path<Uuid>,
+    StrictJson(payload): StrictJson<PreviewVote>,
+) -> Result<Json<PollResults>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    if poll.voting_mode == VotingMode::Ranked {
+        return Err(ApiError::Unprocessable("Vote previews are not available for ranked polls".to_string()));
+    }
+    let mut counts = fetch_option_counts(&pool, &poll).await?;
+    let ballots = fetch_ballot_count(&pool, &poll, &counts).await? + 1;
+
+    let count = usize::try_from(payload.option_index)
+        .ok()
+        .and_then(|i| counts.get_mut(i))
+        .ok_or(ApiError::Validation("Invalid option index".to_string()))?;
+    *count += 1;
+
+    let prices = fetch_option_prices(&pool, &poll).await?;
+    Ok(Json(build_results(&poll, &counts, ballots, &prices)))
+}
+
+/// Reports which optional features are switched on.
+#[utoipa::path(
+    get,
+    path = "/api/admin/features",
+    tag = "service",
+    responses(
+        (status = 200, description = "State of every feature", body = FeatureStates),
+        (status = 401, description = "Missing admin token", body = ErrorBody),
+        (status = 403, description = "Invalid admin token", body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn list_features(_admin: AdminAuth, State(features): State<FeatureFlags>) -> Json<FeatureStates> {
+    Json(features.states())
+}
+
+/// Switches an optional feature (`prices`, `streaming` or `webhooks`) on or
+/// off until the next restart. Endpoints of a disabled feature answer `404`.
+#[utoipa::path(
+    put,
+    path = "/api/admin/features/{name}",
+    tag = "service",
+    params(("name" = String, Path, description = "Feature name, e.g. `prices`")),
+    request_body = FeatureToggle,
+    responses(
+        (status = 200, description = "State of every feature after the change", body = FeatureStates),
+        (status = 401, description = "Missing admin token", body = ErrorBody),
+        (status = 403, description = "Invalid admin token", body = ErrorBody),
+        (status = 404, description = "Unknown feature", body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn set_feature(
+    _admin: AdminAuth,
+    State(features): State<FeatureFlags>,
+    Path(name): Path<String>,
+    StrictJson(payload): StrictJson<FeatureToggle>,
+) -> Result<Json<FeatureStates>, ApiError> {
+    let feature: Feature = name.parse().map_err(ApiError::NotFound)?;
+    features.set(feature, payload.enabled);
+    info!("Feature {} {}", feature.name(), if payload.enabled { "enabled" } else { "disabled" });
+    Ok(Json(features.states()))
+}
+
+/// Returns the current server time in RFC 3339.
+#[utoipa::path(
+    get,
+    path = "/api/time",
+    tag = "service",
+    responses(
+        (status = 200, description = "Current server time", body = ServerTime),
+    ),
+)]
+pub async fn get_server_time() -> Json<ServerTime> {
+    Json(ServerTime { server_time: Utc::now() })
+}
+
+/// Serves the process metrics in the Prometheus text format.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "service",
+    responses(
+        (status = 200, description = "Prometheus text exposition", content_type = "text/plain", body = String),
+    ),
+)]
+pub async fn get_metrics() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], metrics().render())
+}
+
+/// Longest `/health` waits for the database before reporting it degraded.
+const HEALTH_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Readiness probe: `200` when the database answers a trivial query in time,
+/// `503` otherwise. With `READINESS_CHECKS_BINANCE` Binance reachability is
+/// reported too (checked at most every 30 seconds), and only fails the probe
+/// with `READINESS_REQUIRES_BINANCE`.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "service",
+    responses(
+        (status = 200, description = "Database reachable", body = HealthStatus),
+        (status = 503, description = "Database, or a required Binance, unreachable", body = HealthStatus),
+    ),
+)]
+pub async fn health(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    State(binance): State<BinanceClient>,
+) -> (StatusCode, Json<HealthStatus>) {
+    let ping = tokio::time::timeout(HEALTH_CHECK_TIMEOUT, sqlx::query!("SELECT 1 as one").fetch_one(&pool)).await;
+    let database_ok = match ping {
+        Ok(Ok(_)) => true,
+        Ok(Err(e)) => {
+            warn!("Health check query failed: {}", e);
+            false
+        }
+        Err(_) => {
+            warn!("Health check query timed out after {:?}", HEALTH_CHECK_TIMEOUT);
+            false
+        }
+    };
+
+    let binance_ok = if config.readiness_checks_binance {
+        Some(binance.is_reachable().await)
+    } else {
+        None
+    };
+    let ready = database_ok && !(config.readiness_requires_binance && binance_ok == Some(false));
+
+    let status = HealthStatus {
+        status: if ready { "ok" } else { "degraded" },
+        upstream: binance_ok.map(|ok| UpstreamStatus { binance: if ok { "ok" } else { "unreachable" } }),
+    };
+    let code = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (code, Json(status))
+}
+
+/// Liveness probe: answers `200` without touching the database.
+#[utoipa::path(
+    get,
+    path = "/live",
+    tag = "service",
+    responses(
+        (status = 200, description = "Process is running"),
+    ),
+)]
+pub async fn live() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Lists live coin prices from Binance, optionally only for `symbols`
+/// (`404` if any is unknown) and truncated to `limit` entries. While Binance
+/// is unreachable the last fetched prices are served, flagged `stale`.
+#[utoipa::path(
+    get,
+    path = "/api/coins",
+    tag = "coins",
+    params(CoinsParams),
+    responses(
+        (status = 200, description = "Live or cached prices", body = [CoinPrice]),
+        (status = 404, description = "Symbol not listed on Binance", body = ErrorBody),
+        (status = 502, description = "Binance unavailable and no cached prices", body = ErrorBody),
+    ),
+)]
+pub async fn get_coins(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    State(binance): State<BinanceClient>,
+    State(features): State<FeatureFlags>,
+    Query(params): Query<CoinsParams>,
+) -> Result<Json<Vec<CoinPrice>>, ApiError> {
+    features.require(Feature::Prices)?;
+    let wanted: Option<Vec<String>> = params
+        .symbols
+        .map(|symbols| {
+            symbols
+                .split(',')
+                .map(|s| s.trim().to_uppercase())
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|symbols| !symbols.is_empty());
+
+    // Ask Binance for just the requested symbols rather than the whole list
+    let live = match &wanted {
+        Some(symbols) => binance.fetch_prices(symbols).await,
+        None => binance.fetch_crypto_prices().await,
+    };
+    let mut prices = match live {
+        Ok(prices) => fresh_prices(&pool, prices),
+        Err(e) => cached_prices(&pool, &config, e, wanted.as_deref()).await?,
+    };
+
+    if let Some(limit) = params.limit {
+        prices.truncate(limit);
+    }
+
+    Ok(Json(prices))
+}
+
+/// Retrieves the live Binance price of one symbol, or its last fetched price,
+/// flagged `stale`, while Binance is unreachable.
+#[utoipa::path(
+    get,
+    path = "/api/coins/{symbol}",
+    tag = "coins",
+    params(("symbol" = String, Path, description = "Binance symbol, e.g. `BTCUSDT`")),
+    responses(
+        (status = 200, description = "Live or cached price", body = CoinPrice),
+        (status = 404, description = "Symbol not listed on Binance", body = ErrorBody),
+        (status = 502, description = "Binance unavailable and no cached price", body = ErrorBody),
+    ),
+)]
+pub async fn get_coin(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    State(binance): State<BinanceClient>,
+    State(features): State<FeatureFlags>,
+    Path(symbol): Path<String>,
+) -> Result<Json<CoinPrice>, ApiError> {
+    features.require(Feature::Prices)?;
+    let symbol = symbol.trim().to_uppercase();
+    let mut prices = match binance.fetch_price(&symbol).await {
+        Ok(price) => fresh_prices(&pool, vec![price]),
+        Err(e) => cached_prices(&pool, &config, e, Some(std::slice::from_ref(&symbol))).await?,
+    };
+    Ok(Json(prices.remove(0)))
+}
+
+/// Marks live `prices` as fresh and records them in the price cache in the
+/// background, so a slow cache write never delays the response.
+fn fresh_prices(pool: &PgPool, prices: Vec<TickerPrice>) -> Vec<CoinPrice> {
+    let pool = pool.clone();
+    let cached = prices.clone();
+    tokio::spawn(async move {
+        if let Err(e) = price_cache::store(&pool, &cached).await {
+            warn!("Failed to cache Binance prices: {}", e);
+        }
+    });
+
+    let fetched_at = Utc::now();
+    prices
+        .into_iter()
+        .map(|ticker| CoinPrice { ticker, stale: false, fetched_at })
+        .collect()
+}
+
+/// Falls back to the cached prices of `symbols` (every cached one when `None`)
+/// after a failed live fetch. Unknown symbols, a disabled `PRICE_FALLBACK` or
+/// an empty cache answer with the error of the live fetch instead.
+async fn cached_prices(
+    pool: &PgPool,
+    config: &Config,
+    e: BinanceError,
+    symbols: Option<&[String]>,
+) -> Result<Vec<CoinPrice>, ApiError> {
+    if !config.price_fallback || matches!(e, BinanceError::SymbolNotFound(_)) {
+        return Err(binance_error(e));
+    }
+
+    let cached = price_cache::load(pool, symbols).await.unwrap_or_else(|cache_error| {
+        error!("Failed to read cached prices: {}", cache_error);
+        Vec::new()
+    });
+    if cached.is_empty() {
+        return Err(binance_error(e));
+    }
+    warn!("Binance unavailable, serving cached prices: {}", e);
+    Ok(cached)
+}
+
+/// Streams live prices over a WebSocket: the latest known price of each symbol
+/// in `?symbols=`, then every update to them, each as a `TickerPrice` JSON text
+/// message. A client that falls behind gets a `PriceResync` with the current
+/// prices instead of the updates it missed. Prices are only tracked for
+/// symbols that are options of active polls.
+#[utoipa::path(
+    get,
+    path = "/api/prices/ws",
+    tag = "coins",
+    params(PriceStreamParams),
+    responses(
+        (status = 101, description = "WebSocket of `TickerPrice` messages"),
+        (status = 400, description = "No symbols given", body = ErrorBody),
+    ),
+)]
+pub async fn stream_prices(
+    State(prices): State<PriceStream>,
+    State(features): State<FeatureFlags>,
+    Query(params): Query<PriceStreamParams>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    features.require(Feature::Prices)?;
+    let symbols: HashSet<String> = params
+        .symbols
+        .split(',')
+        .map(|s| s.trim().to_uppercase())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if symbols.is_empty() {
+        return Err(ApiError::Validation("symbols must list at least one symbol".to_string()));
+    }
+
+    Ok(ws.on_upgrade(move |socket| forward_prices(socket, prices, symbols)))
+}
+
+/// Sends the prices of `symbols` down `socket` until the client disconnects.
+async fn forward_prices(mut socket: WebSocket, prices: PriceStream, symbols: HashSet<String>) {
+    // Subscribe before taking the snapshot so no update slips in between
+    let mut updates = prices.subscribe();
+    for price in prices.snapshot(&symbols) {
+        if send_price(&mut socket, &price).await.is_err() {
+            return;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            delivery = prices.next_for(&mut updates, &symbols) => {
+                let json = match delivery {
+                    Some(Delivery::Price(price)) => serde_json::to_string(&price).expect("TickerPrice serializes to JSON"),
+                    Some(Delivery::Resync(resync)) => {
+                        serde_json::to_string(&resync).expect("PriceResync serializes to JSON")
+                    }
+                    None => return,
+                };
+                if socket.send(Message::Text(json.into())).await.is_err() {
+                    return;
+                }
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+async fn send_price(socket: &mut WebSocket, price: &TickerPrice) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(price).expect("TickerPrice serializes to JSON");
+    socket.send(Message::Text(json.into())).await
+}
+
+/// Maps unknown symbols to `404` and every other Binance failure to `502`.
+fn binance_error(e: BinanceError) -> ApiError {
+    match e {
+        BinanceError::SymbolNotFound(symbol) => ApiError::NotFound(format!("Symbol not listed on Binance: {}", symbol)),
+        e => {
+            error!("Failed to fetch prices from Binance: {}", e);
+            ApiError::Upstream("Failed to fetch prices from Binance".to_string())
+        }
+    }
+}
+
+/// Retrieves the per-option vote counts of a poll as Prometheus exposition text.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/results.prom",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    responses(
+        (status = 200, description = "Per-option vote counts as Prometheus text", content_type = "text/plain", body = String),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn get_results_prom(
+    State(pool): State<PgPool>,
+    Path(poll_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    let counts = fetch_option_counts(&pool, &poll).await?;
+
+    // Emit a line for every option, including those nobody voted for yet
+    let mut body = String::from(
+        "# HELP poll_option_votes Number of votes cast for a poll option.\n# TYPE poll_option_votes gauge\n",
+    );
+    for (option, count) in poll.options.iter().zip(&counts) {
+        body.push_str(&format!(
+            "poll_option_votes{{poll_id=\"{}\",option=\"{}\"}} {}\n",
+            poll.id,
+            escape_label_value(option),
+            count
+        ));
+    }
 
-    Ok(Json(results))
-}
\ No newline at end of file
+    Ok(([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body))
+}
+
+/// Downloads a poll's results as CSV, one row per option.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/results.csv",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    responses(
+        (status = 200, description = "Results as CSV", content_type = "text/csv", body = String),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn get_results_csv(
+    State(pool): State<PgPool>,
+    Path(poll_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    let results = fetch_results(&pool, &poll).await?;
+
+    let mut body = String::from("option_index,option_label,count,percentage\n");
+    for result in &results.results {
+        body.push_str(&format!(
+            "{},{},{},{:.2}\n",
+            result.option_index,
+            escape_csv_field(&result.option_label),
+            result.count,
+            result.percentage
+        ));
+    }
+
+    let disposition = format!("attachment; filename=\"{}-results.csv\"", filename_slug(&poll.title));
+    Ok((
+        [(header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()), (header::CONTENT_DISPOSITION, disposition)],
+        body,
+    ))
+}
+
+/// Retrieves a poll's results as whole percentages in option order, plus the total.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/results/compact",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    responses(
+        (status = 200, description = "Whole percentages in option order", body = CompactResults),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn get_compact_results(
+    State(pool): State<PgPool>,
+    Path(poll_id): Path<Uuid>,
+) -> Result<Json<CompactResults>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    let counts = fetch_option_counts(&pool, &poll).await?;
+
+    Ok(Json(CompactResults {
+        percentages: whole_percentages(&counts),
+        total_votes: fetch_ballot_count(&pool, &poll, &counts).await?,
+    }))
+}
+
+/// Reports the leading option of a poll, or the tie between several; for a
+/// ranked poll, that of the final instant-runoff round. The result is
+/// `provisional` until the poll stops taking votes. The `margin` over the
+/// runner-up is flagged `is_close` within `CLOSE_RACE_THRESHOLD_PERCENT`, and
+/// the leading count `passed` when it reaches the poll's `per_option_threshold`.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/winner",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    responses(
+        (status = 200, description = "Leading option or tie", body = PollWinner),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn get_winner(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    Path(poll_id): Path<Uuid>,
+) -> Result<Json<PollWinner>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    Ok(Json(fetch_winner(&pool, &config, &poll).await?))
+}
+
+/// Bundles everything public about a poll in one self-contained document for
+/// sharing or archiving: the poll, its results, its votes per minute and its
+/// winner. Voter identities are left out, as everywhere outside the admin API.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/bundle",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    responses(
+        (status = 200, description = "The poll's full state", body = PollBundle),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn get_bundle(
+    State(pool): State<PgPool>,
+    State(config): State<Arc<Config>>,
+    Path(poll_id): Path<Uuid>,
+) -> Result<Json<PollBundle>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    let results = fetch_results(&pool, &poll).await?;
+    let timeseries = fetch_timeseries(&pool, &poll).await?;
+    let winner = fetch_winner(&pool, &config, &poll).await?;
+    Ok(Json(PollBundle { poll, results, timeseries, winner }))
+}
+
+/// Votes of `poll` per minute, counted like its results, oldest minute first.
+async fn fetch_timeseries(pool: &PgPool, poll: &Poll) -> Result<Vec<TimeseriesBucket>, ApiError> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT date_trunc('minute', created_at) as "start!", option_index, COUNT(*) as "count!"
+        FROM votes
+        WHERE poll_id = $1 AND (rank = 1 OR $2)
+        GROUP BY 1, option_index
+        ORDER BY 1
+        "#,
+        poll.id,
+        poll.voting_mode == VotingMode::Approval
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch vote timeseries: {}", e);
+        ApiError::Database("Failed to fetch vote timeseries".to_string())
+    })?;
+
+    let mut buckets: Vec<TimeseriesBucket> = Vec::new();
+    for row in rows {
+        if buckets.last().is_none_or(|bucket| bucket.start != row.start) {
+            buckets.push(TimeseriesBucket { start: row.start, counts: vec![0; poll.options.len()] });
+        }
+        let bucket = buckets.last_mut().expect("a bucket was just pushed");
+        if let Some(count) = usize::try_from(row.option_index).ok().and_then(|i| bucket.counts.get_mut(i)) {
+            *count = row.count;
+        }
+    }
+    Ok(buckets)
+}
+
+/// Leading option or tie of `poll`; see [`get_winner`].
+async fn fetch_winner(pool: &PgPool, config: &Config, poll: &Poll) -> Result<PollWinner, ApiError> {
+    let counts = match poll.voting_mode {
+        VotingMode::Ranked => fetch_runoff(pool, poll).await?.pop().map(|round| round.counts).unwrap_or_default(),
+        _ => fetch_option_counts(pool, poll).await?,
+    };
+
+    let provisional = poll.closed_at.is_none() && Utc::now() <= poll.expires_at + config.vote_grace_period;
+    let count = counts.iter().copied().max().unwrap_or(0);
+    let leaders: Vec<i32> = (0..).zip(&counts).filter(|&(_, &c)| c == count).map(|(i, _)| i).collect();
+
+    let no_votes = count == 0;
+    let tie = !no_votes && leaders.len() > 1;
+    let winner = (!no_votes && !tie).then(|| leaders[0]);
+    let margin = winner_margin(&counts);
+    let is_close = is_close_race(margin.as_ref(), config.close_race_threshold_percent);
+
+    Ok(PollWinner {
+        provisional,
+        no_votes,
+        option_index: winner,
+        option_label: winner.map(|i| poll.options[i as usize].clone()),
+        count,
+        tie,
+        tied_indices: if tie { leaders } else { Vec::new() },
+        margin,
+        is_close,
+        passed: passes_threshold(poll, count),
+    })
+}
+
+/// Lead of the top count over the second highest, or `None` when there is no
+/// runner-up to compare with or no votes at all.
+fn winner_margin(counts: &[i64]) -> Option<WinnerMargin> {
+    let total: i64 = counts.iter().sum();
+    if counts.len() < 2 || total == 0 {
+        return None;
+    }
+    let mut sorted = counts.to_vec();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    let votes = sorted[0] - sorted[1];
+    Some(WinnerMargin { votes, percentage: votes as f64 * 100.0 / total as f64 })
+}
+
+/// Whether a lead of `margin` is within `threshold` percentage points.
+fn is_close_race(margin: Option<&WinnerMargin>, threshold: f64) -> bool {
+    margin.is_some_and(|margin| margin.percentage <= threshold)
+}
+
+/// Retrieves total votes and distinct voters of a poll; in an approval poll
+/// every approved option counts as a vote.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/stats",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id")),
+    responses(
+        (status = 200, description = "Vote totals", body = PollStats),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn get_stats(
+    State(pool): State<PgPool>,
+    Path(poll_id): Path<Uuid>,
+) -> Result<Json<PollStats>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+
+    // Like the results, every approval counts as a vote but only first preferences
+    let stats = sqlx::query_as!(
+        PollStats,
+        r#"
+        SELECT
+            COUNT(*) as "total_votes!",
+            COUNT(DISTINCT voter_ip_hash) as "unique_voters!",
+            MIN(created_at) as first_vote_at,
+            MAX(created_at) as last_vote_at
+        FROM votes
+        WHERE poll_id = $1 AND (rank = 1 OR $2)
+        "#,
+        poll.id,
+        poll.voting_mode == VotingMode::Approval
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch poll stats: {}", e);
+        ApiError::Database("Failed to fetch poll stats".to_string())
+    })?;
+
+    Ok(Json(stats))
+}
+
+/// Returns a page of a poll's votes in the order they were cast, for replaying
+/// how the results evolved (`?limit=` up to 1000, default 100, `?offset=`, `?speed=`).
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/replay",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id"), ReplayParams),
+    responses(
+        (status = 200, description = "A page of votes in casting order", body = ReplayPage),
+        (status = 400, description = "Invalid speed", body = ErrorBody),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn get_replay(
+    State(pool): State<PgPool>,
+    Path(poll_id): Path<Uuid>,
+    Query(params): Query<ReplayParams>,
+) -> Result<Json<ReplayPage>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let speed = params.speed.unwrap_or(1.0);
+    if !(speed.is_finite() && speed > 0.0) {
+        return Err(ApiError::Validation("speed must be a positive number".to_string()));
+    }
+
+    // Only the choice and timestamp leave the database, never the voter identity.
+    // Like the results, every approval is replayed but only first preferences.
+    let rows = sqlx::query!(
+        r#"
+        SELECT option_index, created_at
+        FROM votes
+        WHERE poll_id = $1 AND (rank = 1 OR $4)
+        ORDER BY created_at ASC, id ASC
+        LIMIT $2 OFFSET $3
+        "#,
+        poll.id,
+        limit,
+        offset,
+        poll.voting_mode == VotingMode::Approval
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch vote replay: {}", e);
+        ApiError::Database("Failed to fetch vote replay".to_string())
+    })?;
+
+    let votes = rows
+        .into_iter()
+        .map(|r| ReplayVote {
+            option_index: r.option_index,
+            voted_at: r.created_at,
+            replay_offset_ms: ((r.created_at - poll.created_at).num_milliseconds() as f64 / speed) as i64,
+        })
+        .collect();
+
+    Ok(Json(ReplayPage { votes, limit, offset }))
+}
+
+/// Lists a poll's individual votes for audits, oldest first, optionally only
+/// those cast in `[since, until)` (`?limit=` up to 1000, default 100, `?offset=`).
+/// Voters appear only as the stored HMAC of their IP.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/votes",
+    tag = "votes",
+    params(("id" = Uuid, Path, description = "Poll id"), VotesParams),
+    responses(
+        (status = 200, description = "A page of votes in casting order", body = VotesPage),
+        (status = 400, description = "`since` is after `until`", body = ErrorBody),
+        (status = 401, description = "Missing admin token", body = ErrorBody),
+        (status = 403, description = "Invalid admin token", body = ErrorBody),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn list_votes(
+    _admin: AdminAuth,
+    State(pool): State<PgPool>,
+    Path(poll_id): Path<Uuid>,
+    Query(params): Query<VotesParams>,
+) -> Result<Json<VotesPage>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+    let offset = params.offset.unwrap_or(0).max(0);
+    if let (Some(since), Some(until)) = (params.since, params.until) {
+        if since > until {
+            return Err(ApiError::Validation("since must not be after until".to_string()));
+        }
+    }
+
+    let votes = sqlx::query_as!(
+        Vote,
+        r#"
+        SELECT id, poll_id, option_index, rank, voter_ip_hash, created_at,
+            contact_hash IS NOT NULL as "has_contact!"
+        FROM votes
+        WHERE poll_id = $1
+            AND ($2::TIMESTAMPTZ IS NULL OR created_at >= $2)
+            AND ($3::TIMESTAMPTZ IS NULL OR created_at < $3)
+        ORDER BY created_at ASC, id ASC
+        LIMIT $4 OFFSET $5
+        "#,
+        poll.id,
+        params.since,
+        params.until,
+        limit,
+        offset
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to list votes of poll {}: {}", poll.id, e);
+        ApiError::Database("Failed to list votes".to_string())
+    })?;
+
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM votes
+        WHERE poll_id = $1
+            AND ($2::TIMESTAMPTZ IS NULL OR created_at >= $2)
+            AND ($3::TIMESTAMPTZ IS NULL OR created_at < $3)
+        "#,
+        poll.id,
+        params.since,
+        params.until
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to count votes of poll {}: {}", poll.id, e);
+        ApiError::Database("Failed to list votes".to_string())
+    })?;
+
+    Ok(Json(VotesPage { votes, total, limit, offset }))
+}
+
+/// Number of windows averaged for `PollVelocity::moving_average_per_minute`.
+const VELOCITY_AVERAGE_WINDOWS: i64 = 5;
+
+/// Reports how fast a poll is collecting votes over a recent window
+/// (`?window_seconds=`, default 60, clamped to 1..=3600).
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/velocity",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id"), VelocityParams),
+    responses(
+        (status = 200, description = "Recent voting rate", body = PollVelocity),
+        (status = 404, description = "Poll not found", body = ErrorBody),
+    ),
+)]
+pub async fn get_velocity(
+    State(pool): State<PgPool>,
+    Path(poll_id): Path<Uuid>,
+    Query(params): Query<VelocityParams>,
+) -> Result<Json<PollVelocity>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    let window_seconds = params.window_seconds.unwrap_or(60).clamp(1, 3600);
+
+    let now = Utc::now();
+    let window_start = now - Duration::seconds(window_seconds);
+    let average_start = now - Duration::seconds(window_seconds * VELOCITY_AVERAGE_WINDOWS);
+
+    let counts = sqlx::query!(
+        r#"
+        SELECT
+            COUNT(*) FILTER (WHERE created_at > $2) as "recent!",
+            COUNT(*) FILTER (WHERE created_at > $3) as "extended!"
+        FROM votes
+        WHERE poll_id = $1 AND rank = 1
+        "#,
+        poll.id,
+        window_start,
+        average_start
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch vote velocity: {}", e);
+        ApiError::Database("Failed to fetch vote velocity".to_string())
+    })?;
+
+    let per_minute = |votes: i64, seconds: i64| votes as f64 * 60.0 / seconds as f64;
+    Ok(Json(PollVelocity {
+        window_seconds,
+        votes_in_window: counts.recent,
+        votes_per_minute: per_minute(counts.recent, window_seconds),
+        moving_average_per_minute: per_minute(counts.extended, window_seconds * VELOCITY_AVERAGE_WINDOWS),
+    }))
+}
+
+/// Charges one request to the caller's bucket of `limiter`, if limiting is enabled.
+/// Clients without a known IP share one bucket.
+fn throttle(limiter: Option<&KeyedLimiter>, client_ip: Option<IpAddr>) -> Result<(), ApiError> {
+    let Some(limiter) = limiter else {
+        return Ok(());
+    };
+    let key = client_ip.map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+    limiter.try_acquire(&key).map_err(ApiError::RateLimited)
+}
+
+/// Keyed HMAC of the voter's IP as stored in `votes`, so the raw address never
+/// hits the database. Votes are refused when the IP can't be determined.
+fn voter_ip_hash(config: &Config, client_ip: Option<IpAddr>) -> Result<String, ApiError> {
+    let ip = client_ip.ok_or(ApiError::Validation("Cannot determine client IP".to_string()))?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(config.voter_ip_salt.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(ip.to_string().as_bytes());
+    Ok(mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+fn has_forwarding_headers(headers: &HeaderMap) -> bool {
+    headers.contains_key("x-forwarded-for") || headers.contains_key("x-real-ip")
+}
+
+/// Warns that proxy headers are missing, at most once a minute so a
+/// misconfigured proxy doesn't flood the logs.
+fn warn_missing_proxy_headers() {
+    static WARNINGS: LazyLock<LeakyBucket> = LazyLock::new(|| LeakyBucket::new(1.0 / 60.0, 1.0));
+    if WARNINGS.try_acquire().is_ok() {
+        warn!("Vote without X-Forwarded-For or X-Real-IP behind TRUSTED_PROXY_HOPS proxies; check the proxy configuration");
+    }
+}
+
+/// Loose email shape check: one `@`, a dotted domain, no whitespace, at most 254 chars.
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    email.len() <= 254
+        && !local.is_empty()
+        && !domain.contains('@')
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|label| !label.is_empty())
+        && !email.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+/// Most options a poll may have.
+const MAX_OPTIONS: usize = 50;
+/// Longest allowed poll title, in characters.
+const MAX_TITLE_CHARS: usize = 200;
+
+fn validate_title(title: &str) -> Result<(), ApiError> {
+    if title.trim().is_empty() {
+        return Err(ApiError::Validation("Title must not be empty".to_string()));
+    }
+    if title.chars().count() > MAX_TITLE_CHARS {
+        return Err(ApiError::Validation(format!("Title must be at most {} characters", MAX_TITLE_CHARS)));
+    }
+    Ok(())
+}
+
+/// Requires 2 to `MAX_OPTIONS` non-blank options, unique ignoring case and surrounding whitespace.
+fn validate_options(options: &[String]) -> Result<(), ApiError> {
+    if options.len() < 2 {
+        return Err(ApiError::Validation("A poll needs at least 2 options".to_string()));
+    }
+    if options.len() > MAX_OPTIONS {
+        return Err(ApiError::Validation(format!("A poll can have at most {} options", MAX_OPTIONS)));
+    }
+    if options.iter().any(|o| o.trim().is_empty()) {
+        return Err(ApiError::Validation("Options must not be empty".to_string()));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    if let Some(duplicate) = options.iter().find(|o| !seen.insert(o.trim().to_lowercase())) {
+        return Err(ApiError::Validation(format!("Duplicate option: {}", duplicate.trim())));
+    }
+    Ok(())
+}
+
+/// Longest allowed `created_by`, in characters.
+const MAX_CREATED_BY_CHARS: usize = 100;
+
+/// Requires a non-blank author of at most `MAX_CREATED_BY_CHARS` without control characters.
+fn validate_created_by(created_by: &str) -> Result<(), ApiError> {
+    if created_by.is_empty() {
+        return Err(ApiError::Validation("created_by must not be empty".to_string()));
+    }
+    if created_by.chars().count() > MAX_CREATED_BY_CHARS {
+        return Err(ApiError::Validation(format!(
+            "created_by must be at most {} characters",
+            MAX_CREATED_BY_CHARS
+        )));
+    }
+    if created_by.chars().any(char::is_control) {
+        return Err(ApiError::Validation("created_by must not contain control characters".to_string()));
+    }
+    Ok(())
+}
+
+/// Requires a positive duration within `POLL_MAX_DURATION_MINUTES`.
+fn validate_duration(config: &Config, expires_in_minutes: i64) -> Result<(), ApiError> {
+    if expires_in_minutes <= 0 {
+        return Err(ApiError::Validation("expires_in_minutes must be positive".to_string()));
+    }
+    if let Some(max) = config.poll_max_duration_minutes.filter(|&max| expires_in_minutes > max) {
+        return Err(ApiError::Validation(format!("Polls may run for at most {} minutes", max)));
+    }
+    Ok(())
+}
+
+/// Turns the relative or absolute expiry of a new poll into its deadline. At
+/// most one of the two may be given; without either the poll runs for
+/// `POLL_DEFAULT_DURATION_MINUTES`.
+fn resolve_expiry(
+    config: &Config,
+    expires_in_minutes: Option<i64>,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<DateTime<Utc>, ApiError> {
+    let now = Utc::now();
+    match (expires_in_minutes, expires_at) {
+        (Some(minutes), None) => {
+            validate_duration(config, minutes)?;
+            Ok(now + Duration::minutes(minutes))
+        }
+        (None, Some(expires_at)) if expires_at <= now => {
+            Err(ApiError::Validation("expires_at must be in the future".to_string()))
+        }
+        (None, Some(expires_at)) => match config.poll_max_duration_minutes {
+            Some(max) if expires_at > now + Duration::minutes(max) => Err(ApiError::Validation(format!(
+                "expires_at must be at most {} minutes away",
+                max
+            ))),
+            _ => Ok(expires_at),
+        },
+        (Some(_), Some(_)) => Err(ApiError::Validation(
+            "Give either expires_in_minutes or expires_at, not both".to_string(),
+        )),
+        (None, None) => Ok(now + Duration::minutes(config.poll_default_duration_minutes)),
+    }
+}
+
+/// Structural BCP-47 check: a 2-8 letter primary language subtag followed by
+/// 1-8 character alphanumeric subtags, e.g. `fr`, `fr-FR`, `zh-Hant-TW`.
+fn is_valid_language_tag(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let primary_ok = subtags
+        .next()
+        .is_some_and(|s| (2..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphabetic()));
+    primary_ok && subtags.all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Hex SHA-256 of the normalized contact, so the raw address never hits the database.
+fn hash_contact(contact: &str) -> String {
+    Sha256::digest(contact.to_lowercase().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Settings of a poll beyond its title, options and expiry.
+struct PollSettings<'a> {
+    per_option_threshold: i32,
+    locale: Option<&'a str>,
+    allow_vote_change: bool,
+    voting_mode: VotingMode,
+    created_by: Option<&'a str>,
+    /// Starting price per option, recorded when the poll asked for a snapshot.
+    option_prices: Option<Vec<Option<String>>>,
+}
+
+/// Inserts a poll and prunes old ones beyond `MAX_POLLS_RETAINED`, in one transaction.
+async fn insert_poll(
+    pool: &PgPool,
+    config: &Config,
+    title: &str,
+    options: &[String],
+    expires_at: DateTime<Utc>,
+    settings: PollSettings<'_>,
+) -> Result<Poll, ApiError> {
+    let mut tx = pool.begin().await.map_err(|e| {
+        error!("Failed to start transaction: {}", e);
+        ApiError::Database("Database transaction failed".to_string())
+    })?;
+
+    if let (Some(author), Some(limit)) = (settings.created_by, config.max_active_polls_per_author) {
+        // Serialize creations per author so concurrent requests can't both pass the count
+        sqlx::query_scalar!(r#"SELECT 1 as "locked!" FROM pg_advisory_xact_lock(hashtext($1))"#, author)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock the polls of {}: {}", author, e);
+                ApiError::Database("Failed to create poll".to_string())
+            })?;
+        let open_polls = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!"
+            FROM polls
+            WHERE created_by = $1 AND closed_at IS NULL AND expires_at > NOW()
+            "#,
+            author
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Failed to count open polls of {}: {}", author, e);
+            ApiError::Database("Failed to create poll".to_string())
+        })?;
+        if open_polls >= limit {
+            return Err(ApiError::TooManyActivePolls(limit));
+        }
+    }
+
+    // Insert the new poll
+    let poll = sqlx::query_as!(
+        Poll,
+        r#"
+        INSERT INTO polls (
+            title, options, expires_at, per_option_threshold, locale, allow_vote_change, voting_mode, created_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id, title, options, expires_at, created_at, per_option_threshold, locale, closed_at, allow_vote_change,
+            voting_mode as "voting_mode: VotingMode", created_by
+        "#,
+        title,
+        options,
+        expires_at,
+        settings.per_option_threshold,
+        settings.locale,
+        settings.allow_vote_change,
+        settings.voting_mode as VotingMode,
+        settings.created_by
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!("Failed to insert poll: {}", e);
+        ApiError::Database("Failed to create poll".to_string())
+    })?;
+
+    if let Some(prices) = &settings.option_prices {
+        sqlx::query!(
+            r#"
+            INSERT INTO poll_option_prices (poll_id, option_index, price)
+            SELECT $1, (position - 1)::INTEGER, price
+            FROM UNNEST($2::TEXT[]) WITH ORDINALITY AS t(price, position)
+            "#,
+            poll.id,
+            prices as &[Option<String>]
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Failed to record option prices: {}", e);
+            ApiError::Database("Failed to create poll".to_string())
+        })?;
+    }
+
+    // Prune the oldest closed polls (votes cascade) to stay within the retention cap
+    if let Some(max_polls) = config.max_polls_retained {
+        sqlx::query!(
+            r#"
+            DELETE FROM polls
+            WHERE id IN (
+                SELECT id FROM polls
+                WHERE expires_at <= NOW() OR closed_at IS NOT NULL
+                ORDER BY created_at ASC
+                LIMIT GREATEST((SELECT COUNT(*) FROM polls) - $1, 0)
+            )
+            "#,
+            max_polls
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Failed to prune old polls: {}", e);
+            ApiError::Database("Failed to prune old polls".to_string())
+        })?;
+    }
+
+    tx.commit().await.map_err(|e| {
+        error!("Failed to commit transaction: {}", e);
+        ApiError::Database("Transaction commit failed".to_string())
+    })?;
+    metrics().polls_created.inc();
+
+    Ok(poll)
+}
+
+/// Retrieves the vote count of one option; `404` if the index isn't one of the poll's options.
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/options/{index}/count",
+    tag = "results",
+    params(("id" = Uuid, Path, description = "Poll id"), ("index" = i64, Path, description = "Option index")),
+    responses(
+        (status = 200, description = "Votes of the option", body = OptionCount),
+        (status = 404, description = "Poll or option not found", body = ErrorBody),
+    ),
+)]
+pub async fn get_option_count(
+    State(pool): State<PgPool>,
+    Path((poll_id, index)): Path<(Uuid, i64)>,
+) -> Result<Json<OptionCount>, ApiError> {
+    let poll = fetch_poll(&pool, poll_id).await?;
+    let (index, label) = usize::try_from(index)
+        .ok()
+        .and_then(|i| Some((i32::try_from(i).ok()?, poll.options.get(i)?.clone())))
+        .ok_or(ApiError::NotFound("Option not found".to_string()))?;
+
+    let count = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM votes
+        WHERE poll_id = $1 AND option_index = $2 AND (rank = 1 OR $3)
+        "#,
+        poll.id,
+        index,
+        poll.voting_mode == VotingMode::Approval
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to count votes for option {}: {}", index, e);
+        ApiError::Database("Failed to fetch option count".to_string())
+    })?;
+
+    Ok(Json(OptionCount { index, label, count }))
+}
+
+/// Fetches the most recently created poll, if any.
+async fn fetch_latest_poll(pool: &PgPool) -> Result<Option<Poll>, ApiError> {
+    sqlx::query_as!(
+        Poll,
+        r#"
+        SELECT id, title, options, expires_at, created_at, per_option_threshold, locale, closed_at, allow_vote_change,
+            voting_mode as "voting_mode: VotingMode", created_by
+        FROM polls
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch current poll: {}", e);
+        ApiError::Database("Failed to fetch current poll".to_string())
+    })
+}
+
+/// Fetches a poll by id, mapping a missing row to `404`.
+async fn fetch_poll(pool: &PgPool, poll_id: Uuid) -> Result<Poll, ApiError> {
+    sqlx::query_as!(
+        Poll,
+        r#"
+        SELECT id, title, options, expires_at, created_at, per_option_threshold, locale, closed_at, allow_vote_change,
+            voting_mode as "voting_mode: VotingMode", created_by
+        FROM polls
+        WHERE id = $1
+        "#,
+        poll_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch poll {}: {}", poll_id, e);
+        ApiError::Database("Failed to fetch poll".to_string())
+    })?
+    .ok_or(ApiError::PollNotFound)
+}
+
+/// Counts the votes of every option of `poll`, indexed like `poll.options`:
+/// every approval of an approval poll, but only first preferences of a ranked one.
+async fn fetch_option_counts(pool: &PgPool, poll: &Poll) -> Result<Vec<i64>, ApiError> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT option_index, COUNT(*) as count
+        FROM votes
+        WHERE poll_id = $1 AND (rank = 1 OR $2)
+        GROUP BY option_index
+        "#,
+        poll.id,
+        poll.voting_mode == VotingMode::Approval
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch results: {}", e);
+        ApiError::Database("Failed to fetch results".to_string())
+    })?;
+
+    let mut counts = vec![0; poll.options.len()];
+    for row in rows {
+        if let Some(count) = usize::try_from(row.option_index).ok().and_then(|i| counts.get_mut(i)) {
+            *count = row.count.unwrap_or(0);
+        }
+    }
+    Ok(counts)
+}
+
+/// Fetches the starting price of every option of `poll`, indexed like
+/// `poll.options`; all `None` when no prices were captured.
+async fn fetch_option_prices(pool: &PgPool, poll: &Poll) -> Result<Vec<Option<String>>, ApiError> {
+    let rows = sqlx::query!(
+        r#"SELECT option_index, price FROM poll_option_prices WHERE poll_id = $1"#,
+        poll.id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch option prices: {}", e);
+        ApiError::Database("Failed to fetch results".to_string())
+    })?;
+
+    let mut prices = vec![None; poll.options.len()];
+    for row in rows {
+        if let Some(price) = usize::try_from(row.option_index).ok().and_then(|i| prices.get_mut(i)) {
+            *price = row.price;
+        }
+    }
+    Ok(prices)
+}
+
+/// Counts the ballots cast in `poll`. Outside approval polls every ballot
+/// adds exactly one to the option `counts`, so no query is needed.
+async fn fetch_ballot_count(pool: &PgPool, poll: &Poll, counts: &[i64]) -> Result<i64, ApiError> {
+    if poll.voting_mode != VotingMode::Approval {
+        return Ok(counts.iter().sum());
+    }
+    sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!" FROM votes WHERE poll_id = $1 AND rank = 1"#,
+        poll.id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to count ballots: {}", e);
+        ApiError::Database("Failed to fetch results".to_string())
+    })
+}
+
+/// Runs the instant-runoff count of a ranked `poll` over its stored ballots.
+async fn fetch_runoff(pool: &PgPool, poll: &Poll) -> Result<Vec<RunoffRound>, ApiError> {
+    let rankings = sqlx::query_scalar!(
+        r#"
+        SELECT array_agg(option_index ORDER BY rank) as "ranking!"
+        FROM votes
+        WHERE poll_id = $1
+        GROUP BY voter_ip_hash
+        "#,
+        poll.id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch ranked ballots: {}", e);
+        ApiError::Database("Failed to fetch results".to_string())
+    })?;
+
+    let ballots: Vec<Vec<usize>> = rankings
+        .into_iter()
+        .map(|ranking| ranking.into_iter().filter_map(|i| usize::try_from(i).ok()).collect())
+        .collect();
+    Ok(runoff::instant_runoff(poll.options.len(), &ballots))
+}
+
+/// Fetches the current results of `poll`, with the runoff rounds of a ranked poll.
+pub async fn fetch_results(pool: &PgPool, poll: &Poll) -> Result<PollResults, ApiError> {
+    let counts = fetch_option_counts(pool, poll).await?;
+    let ballots = fetch_ballot_count(pool, poll, &counts).await?;
+    let prices = fetch_option_prices(pool, poll).await?;
+    let mut results = build_results(poll, &counts, ballots, &prices);
+    if poll.voting_mode == VotingMode::Ranked {
+        results.rounds = Some(fetch_runoff(pool, poll).await?);
+    }
+    Ok(results)
+}
+
+/// Builds the results of `poll` from its per-option `counts`, the number of
+/// `ballots` and the starting `prices`.
+fn build_results(poll: &Poll, counts: &[i64], ballots: i64, prices: &[Option<String>]) -> PollResults {
+    let results = (0..)
+        .zip(poll.options.iter().zip(counts).zip(prices))
+        .map(|(option_index, ((label, &count), price))| OptionResult {
+            option_index,
+            option_label: label.clone(),
+            count,
+            percentage: if ballots == 0 {
+                0.0
+            } else {
+                count as f64 * 100.0 / ballots as f64
+            },
+            passed: passes_threshold(poll, count),
+            starting_price: price.clone(),
+        })
+        .collect();
+
+    PollResults {
+        poll_id: poll.id,
+        title: poll.title.clone(),
+        options: poll.options.clone(),
+        expires_at: poll.expires_at,
+        voting_mode: poll.voting_mode,
+        total_votes: ballots,
+        results,
+        rounds: None,
+    }
+}
+
+/// Whether `count` votes reach the `per_option_threshold` of `poll`; `None`
+/// when the threshold is 0, i.e. disabled.
+fn passes_threshold(poll: &Poll, count: i64) -> Option<bool> {
+    (poll.per_option_threshold > 0).then(|| count >= i64::from(poll.per_option_threshold))
+}
+
+/// Converts vote counts into whole percentages summing to exactly 100 using
+/// the largest remainder method, or all zeros when nobody has voted.
+fn whole_percentages(counts: &[i64]) -> Vec<i64> {
+    let total: i64 = counts.iter().sum();
+    if total == 0 {
+        return vec![0; counts.len()];
+    }
+
+    let mut percentages: Vec<i64> = counts.iter().map(|c| c * 100 / total).collect();
+    let shortfall = 100 - percentages.iter().sum::<i64>();
+
+    // Hand the leftover points to the options that lost the most to truncation
+    let mut by_remainder: Vec<usize> = (0..counts.len()).collect();
+    by_remainder.sort_by_key(|&i| std::cmp::Reverse(counts[i] * 100 % total));
+    for &i in by_remainder.iter().take(shortfall as usize) {
+        percentages[i] += 1;
+    }
+    percentages
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break.
+fn escape_csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Lowercase ASCII slug of a poll title for download filenames, e.g.
+/// `Best coin 2025?` becomes `best-coin-2025`; `poll` if nothing is left.
+fn filename_slug(title: &str) -> String {
+    let slug = title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() { "poll".to_string() } else { slug }
+}
+
+/// Escapes a Prometheus label value (backslash, double quote and newline).
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, send};
+    use axum::body::Body;
+    use axum::http::{Method, Request};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn percentages_sum_to_exactly_100() {
+        assert_eq!(whole_percentages(&[1, 1, 1]), vec![34, 33, 33]);
+        assert_eq!(whole_percentages(&[2, 1]), vec![67, 33]);
+        assert_eq!(whole_percentages(&[5, 0, 5]), vec![50, 0, 50]);
+    }
+
+    #[test]
+    fn leftover_points_go_to_the_largest_remainders() {
+        // 14.28..., 28.57..., 57.14... truncate to 14 + 28 + 57 = 99
+        assert_eq!(whole_percentages(&[1, 2, 4]), vec![14, 29, 57]);
+    }
+
+    #[test]
+    fn percentages_without_votes_are_zero() {
+        assert_eq!(whole_percentages(&[0, 0]), vec![0, 0]);
+        assert_eq!(whole_percentages(&[]), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(escape_csv_field("BTCUSDT"), "BTCUSDT");
+        assert_eq!(escape_csv_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(escape_csv_field("cr\r"), "\"cr\r\"");
+    }
+
+    #[test]
+    fn filename_slugs_keep_ascii_words() {
+        assert_eq!(filename_slug("Best coin 2025?"), "best-coin-2025");
+        assert_eq!(filename_slug("  ¿Qué?  "), "qu");
+        assert_eq!(filename_slug("!!!"), "poll");
+    }
+
+    #[test]
+    fn option_results_serialize_as_named_fields() {
+        let poll = Poll {
+            id: Uuid::nil(),
+            title: "Best coin".to_string(),
+            options: vec!["BTC".to_string(), "ETH".to_string()],
+            expires_at: DateTime::UNIX_EPOCH,
+            created_at: DateTime::UNIX_EPOCH,
+            per_option_threshold: 0,
+            locale: None,
+            closed_at: None,
+            allow_vote_change: false,
+            voting_mode: VotingMode::Single,
+            created_by: None,
+        };
+        let results = build_results(&poll, &[5, 3], 8, &[Some("97000.00".to_string()), None]);
+
+        assert_eq!(
+            serde_json::to_value(&results.results).unwrap(),
+            json!([
+                {
+                    "option_index": 0,
+                    "option_label": "BTC",
+                    "count": 5,
+                    "percentage": 62.5,
+                    "passed": null,
+                    "starting_price": "97000.00"
+                },
+                {
+                    "option_index": 1,
+                    "option_label": "ETH",
+                    "count": 3,
+                    "percentage": 37.5,
+                    "passed": null,
+                    "starting_price": null
+                }
+            ])
+        );
+    }
+
+    fn vote_from(ip: &str, poll_id: Uuid, option_index: i32) -> Request<Body> {
+        let mut request =
+            test_util::request(Method::POST, &format!("/api/polls/{}/vote", poll_id), json!({ "option_index": option_index }));
+        request.headers_mut().insert("x-forwarded-for", ip.parse().unwrap());
+        request
+    }
+
+    #[sqlx::test]
+    async fn only_options_reaching_the_threshold_pass(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let body = json!({ "title": "Listings", "options": ["BTC", "ETH", "SOL"], "per_option_threshold": 2 });
+        let id = test_util::create_poll(&app, body).await;
+        for (ip, option_index) in [("198.51.100.1", 0), ("198.51.100.2", 0), ("198.51.100.3", 1)] {
+            assert_eq!(send(&app, vote_from(ip, id, option_index)).await.0, StatusCode::NO_CONTENT);
+        }
+
+        let uri = format!("/api/polls/{}/results", id);
+        let (_, results) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        let passed: Vec<&Value> = results["results"].as_array().unwrap().iter().map(|r| &r["passed"]).collect();
+        assert_eq!(passed, [&json!(true), &json!(false), &json!(false)]);
+
+        let uri = format!("/api/polls/{}/winner", id);
+        let (_, winner) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(winner["passed"], true);
+    }
+
+    #[sqlx::test]
+    async fn without_a_threshold_nothing_is_marked_passed(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+        assert_eq!(send(&app, vote_from("198.51.100.1", id, 0)).await.0, StatusCode::NO_CONTENT);
+
+        let uri = format!("/api/polls/{}/results", id);
+        let (_, results) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert!(results["results"].as_array().unwrap().iter().all(|r| r["passed"].is_null()), "{}", results);
+        let uri = format!("/api/polls/{}/winner", id);
+        let (_, winner) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert!(winner["passed"].is_null());
+    }
+
+    /// Notifier that records every confirmation instead of delivering it.
+    #[derive(Default)]
+    struct RecordingNotifier(std::sync::Mutex<Vec<VoteConfirmation>>);
+
+    impl crate::notify::Notifier for RecordingNotifier {
+        fn notify(&self, confirmation: VoteConfirmation) {
+            self.0.lock().unwrap().push(confirmation);
+        }
+    }
+
+    #[sqlx::test]
+    async fn contactful_votes_notify_exactly_once(pool: PgPool) {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let mut state = test_util::state(pool, test_util::config(&[]));
+        state.notifier = Some(notifier.clone());
+        let app = test_util::app(state);
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+
+        let uri = format!("/api/polls/{}/vote", id);
+        let mut anonymous = test_util::request(Method::POST, &uri, json!({ "option_index": 0 }));
+        anonymous.headers_mut().insert("x-forwarded-for", "198.51.100.1".parse().unwrap());
+        assert_eq!(send(&app, anonymous).await.0, StatusCode::NO_CONTENT);
+        let contactful = test_util::request(Method::POST, &uri, json!({ "option_index": 1, "contact": " ada@example.com " }));
+        assert_eq!(send(&app, contactful).await.0, StatusCode::NO_CONTENT);
+
+        let sent = notifier.0.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].contact, "ada@example.com");
+        assert_eq!(sent[0].poll_id, id);
+        assert_eq!(sent[0].poll_title, "Best coin");
+        assert_eq!(sent[0].option, "ETH");
+    }
+
+    #[sqlx::test]
+    async fn shuffled_positions_vote_for_the_option_shown(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[("OPTION_ORDER_SECRET", "shuffle-key")])));
+        let body = json!({ "title": "Best coin", "options": ["BTC", "ETH", "SOL", "ADA", "XRP"] });
+        let id = test_util::create_poll(&app, body).await;
+
+        let (status, poll) = send(&app, test_util::request(Method::GET, &format!("/api/polls/{}", id), Value::Null)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(poll["options"], json!(["BTC", "ETH", "SOL", "ADA", "XRP"]));
+        let shown_last = poll["shuffled"]["options"][4].as_str().unwrap().to_string();
+        let vote = json!({ "option_index": 4, "order_token": poll["shuffled"]["order_token"] });
+        let request = test_util::request(Method::POST, &format!("/api/polls/{}/vote", id), vote);
+        assert_eq!(send(&app, request).await.0, StatusCode::NO_CONTENT);
+
+        let uri = format!("/api/polls/{}/results", id);
+        let (_, results) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        let voted: Vec<&Value> = results["results"].as_array().unwrap().iter().filter(|r| r["count"] == 1).collect();
+        assert_eq!(voted.len(), 1);
+        assert_eq!(voted[0]["option_label"], shown_last.as_str());
+    }
+
+    #[sqlx::test]
+    async fn polls_are_not_shuffled_without_a_secret(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+        let (_, poll) = send(&app, test_util::request(Method::GET, &format!("/api/polls/{}", id), Value::Null)).await;
+        assert!(poll.get("shuffled").is_none(), "{}", poll);
+    }
+
+    #[sqlx::test]
+    async fn header_less_votes_without_a_peer_are_rejected(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool.clone(), test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+
+        for _ in 0..2 {
+            let mut vote = test_util::request(Method::POST, &format!("/api/polls/{}/vote", id), json!({ "option_index": 0 }));
+            vote.headers_mut().remove("x-forwarded-for");
+            let (status, body) = send(&app, vote).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["message"], "Cannot determine client IP");
+        }
+        let rows = sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM votes WHERE poll_id = $1"#, id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, 0);
+    }
+
+    #[test]
+    fn voter_hashes_depend_only_on_the_address() {
+        let config = test_util::config(&[]);
+        let hash = |ip: &str| voter_ip_hash(&config, Some(ip.parse().unwrap())).unwrap();
+        assert_eq!(hash("203.0.113.7"), hash("203.0.113.7"));
+        assert_ne!(hash("203.0.113.7"), hash("203.0.113.8"));
+        assert!(!hash("203.0.113.7").contains("203.0.113.7"));
+    }
+
+    #[sqlx::test]
+    async fn second_vote_from_the_same_address_collides(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+
+        assert_eq!(send(&app, vote_from("198.51.100.1", id, 0)).await.0, StatusCode::NO_CONTENT);
+        let (status, body) = send(&app, vote_from("198.51.100.1", id, 1)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"], "already_voted");
+    }
+
+    #[sqlx::test]
+    async fn polls_of_one_author_list_each_other(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let first = test_util::create_poll(&app, json!({ "title": "Q1", "options": ["BTC", "ETH"], "created_by": "ada" })).await;
+        let second = test_util::create_poll(&app, json!({ "title": "Q2", "options": ["BTC", "ETH"], "created_by": "ada" })).await;
+        test_util::create_poll(&app, json!({ "title": "Other", "options": ["BTC", "ETH"], "created_by": "bob" })).await;
+
+        for (poll, other) in [(first, second), (second, first)] {
+            let uri = format!("/api/polls/{}/related", poll);
+            let (status, page) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(page["total"], 1);
+            assert_eq!(page["polls"][0]["id"], other.to_string());
+        }
+    }
+
+    #[sqlx::test]
+    async fn anonymous_polls_have_no_related_polls(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Q1", "options": ["BTC", "ETH"] })).await;
+        test_util::create_poll(&app, json!({ "title": "Q2", "options": ["BTC", "ETH"] })).await;
+
+        let uri = format!("/api/polls/{}/related", id);
+        let (status, page) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["polls"], json!([]));
+        assert_eq!(page["total"], 0);
+    }
+
+    #[sqlx::test]
+    async fn author_results_cover_each_of_their_polls(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let single = json!({ "title": "Q1", "options": ["BTC", "ETH"], "created_by": "ada" });
+        let first = test_util::create_poll(&app, single).await;
+        let approval = json!({ "title": "Q2", "options": ["BTC", "ETH", "SOL"], "created_by": "ada", "voting_mode": "approval" });
+        let second = test_util::create_poll(&app, approval).await;
+        test_util::create_poll(&app, json!({ "title": "Other", "options": ["BTC", "ETH"], "created_by": "bob" })).await;
+
+        assert_eq!(send(&app, vote_from("198.51.100.1", first, 1)).await.0, StatusCode::NO_CONTENT);
+        let uri = format!("/api/polls/{}/vote", second);
+        let approvals = test_util::request(Method::POST, &uri, json!({ "approvals": [0, 2] }));
+        assert_eq!(send(&app, approvals).await.0, StatusCode::NO_CONTENT);
+
+        let unauthenticated = test_util::request(Method::GET, "/api/authors/ada/results", Value::Null);
+        assert_eq!(send(&app, unauthenticated).await.0, StatusCode::UNAUTHORIZED);
+        let request = test_util::admin_request(Method::GET, "/api/authors/ada/results", Value::Null);
+        let (status, page) = send(&app, request).await;
+        assert_eq!(status, StatusCode::OK, "{}", page);
+        assert_eq!(page["total"], 2);
+
+        let counts = |results: &Value| -> Vec<i64> {
+            results["results"].as_array().unwrap().iter().map(|r| r["count"].as_i64().unwrap()).collect()
+        };
+        let newest = &page["polls"][0];
+        assert_eq!(newest["poll_id"], second.to_string());
+        assert_eq!(counts(newest), [1, 0, 1]);
+        assert_eq!(newest["total_votes"], 1);
+        let oldest = &page["polls"][1];
+        assert_eq!(oldest["poll_id"], first.to_string());
+        assert_eq!(counts(oldest), [0, 1]);
+        assert_eq!(oldest["total_votes"], 1);
+    }
+
+    #[sqlx::test]
+    async fn collections_of_an_empty_database_are_empty_pages(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+
+        let (status, page) = send(&app, test_util::request(Method::GET, "/api/polls", Value::Null)).await;
+        assert_eq!(status, StatusCode::OK, "{}", page);
+        assert_eq!(page, json!({ "polls": [], "total": 0, "limit": 20, "offset": 0 }));
+
+        let request = test_util::admin_request(Method::GET, "/api/authors/ada/results", Value::Null);
+        let (status, page) = send(&app, request).await;
+        assert_eq!(status, StatusCode::OK, "{}", page);
+        assert_eq!(page, json!({ "polls": [], "total": 0, "limit": 20, "offset": 0 }));
+    }
+
+    #[sqlx::test]
+    async fn stream_warns_before_closing(pool: PgPool) {
+        let config = test_util::config(&[("POLL_EXPIRING_SOON_SECONDS", "1")]);
+        let app = test_util::app(test_util::state(pool, config));
+        let expires_at = Utc::now() + Duration::seconds(2);
+        let id = test_util::create_poll(&app, json!({ "title": "Quick", "options": ["BTC", "ETH"], "expires_at": expires_at })).await;
+
+        // Ticks are far apart, so both expiry events come from their own deadlines
+        let uri = format!("/api/polls/{}/results/stream?interval_secs=60", id);
+        let (status, _, body) = test_util::send_raw(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(status, StatusCode::OK);
+        let body = String::from_utf8(body).unwrap();
+        let events: Vec<&str> = body.lines().filter_map(|line| line.strip_prefix("event: ")).collect();
+        assert_eq!(events, ["results", "expiring_soon", "closed"]);
+        assert!(Utc::now() >= expires_at);
+    }
+
+    #[sqlx::test]
+    async fn stream_resumes_after_the_last_event_id(pool: PgPool) {
+        let config = test_util::config(&[("POLL_EXPIRING_SOON_SECONDS", "0")]);
+        let app = test_util::app(test_util::state(pool, config));
+        let expires_at = Utc::now() + Duration::seconds(2);
+        let id = test_util::create_poll(&app, json!({ "title": "Quick", "options": ["BTC", "ETH"], "expires_at": expires_at })).await;
+        assert_eq!(send(&app, vote_from("198.51.100.1", id, 0)).await.0, StatusCode::NO_CONTENT);
+        assert_eq!(send(&app, vote_from("198.51.100.2", id, 1)).await.0, StatusCode::NO_CONTENT);
+
+        let resume = |last_event_id: &str| {
+            let uri = format!("/api/polls/{}/results/stream?interval_secs=60", id);
+            let mut request = test_util::request(Method::GET, &uri, Value::Null);
+            request.headers_mut().insert("last-event-id", last_event_id.parse().unwrap());
+            let app = app.clone();
+            async move {
+                let (_, _, body) = test_util::send_raw(&app, request).await;
+                let body = String::from_utf8(body).unwrap();
+                body.lines()
+                    .filter(|line| line.starts_with("event: ") || line.starts_with("id: "))
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            }
+        };
+        let (seen, behind) = tokio::join!(resume("2"), resume("1"));
+        assert_eq!(seen, ["event: closed"]);
+        assert_eq!(behind, ["event: results", "id: 2", "event: closed"]);
+    }
+
+    #[tokio::test]
+    async fn disabling_prices_hides_the_coin_endpoints() {
+        let app = test_util::app(test_util::state(test_util::lazy_pool(), test_util::config(&[])));
+
+        let toggle = json!({ "enabled": false });
+        let anonymous = test_util::request(Method::PUT, "/api/admin/features/prices", toggle.clone());
+        assert_eq!(send(&app, anonymous).await.0, StatusCode::UNAUTHORIZED);
+        let request = test_util::admin_request(Method::PUT, "/api/admin/features/prices", toggle);
+        let (status, states) = send(&app, request).await;
+        assert_eq!(status, StatusCode::OK, "{}", states);
+        assert_eq!(states, json!({ "prices": false, "streaming": true, "webhooks": true }));
+
+        for uri in ["/api/coins", "/api/coins/BTCUSDT"] {
+            let (status, body) = send(&app, test_util::request(Method::GET, uri, Value::Null)).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+            assert_eq!(body["error"], "feature_disabled");
+        }
+
+        let unknown = test_util::admin_request(Method::PUT, "/api/admin/features/binance", json!({ "enabled": true }));
+        assert_eq!(send(&app, unknown).await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    async fn votes_within_the_grace_period_count(pool: PgPool) {
+        let config = test_util::config(&[("VOTE_GRACE_PERIOD_SECONDS", "30")]);
+        let app = test_util::app(test_util::state(pool.clone(), config));
+        let just_late = test_util::create_poll(&app, json!({ "title": "Q1", "options": ["BTC", "ETH"] })).await;
+        let too_late = test_util::create_poll(&app, json!({ "title": "Q2", "options": ["BTC", "ETH"] })).await;
+        for (id, late_by) in [(just_late, 5), (too_late, 60)] {
+            sqlx::query!("UPDATE polls SET expires_at = $2 WHERE id = $1", id, Utc::now() - Duration::seconds(late_by))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(send(&app, vote_from("198.51.100.1", just_late, 0)).await.0, StatusCode::NO_CONTENT);
+        let (status, body) = send(&app, vote_from("198.51.100.1", too_late, 0)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "poll_expired");
+    }
+
+    #[sqlx::test]
+    async fn server_time_is_now(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+        let uri = format!("/api/polls/{}", id);
+
+        for uri in ["/api/time", uri.as_str()] {
+            let (status, body) = send(&app, test_util::request(Method::GET, uri, Value::Null)).await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+            let server_time: DateTime<Utc> = body["server_time"].as_str().unwrap().parse().unwrap();
+            assert!((Utc::now() - server_time).abs() < Duration::seconds(5), "{}: {}", uri, server_time);
+        }
+    }
+
+    #[sqlx::test]
+    async fn creating_beyond_the_cap_prunes_the_oldest_closed_poll(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[("MAX_POLLS_RETAINED", "2")])));
+        let oldest = test_util::create_poll(&app, json!({ "title": "Q1", "options": ["BTC", "ETH"] })).await;
+        let older = test_util::create_poll(&app, json!({ "title": "Q2", "options": ["BTC", "ETH"] })).await;
+        for id in [oldest, older] {
+            let close = test_util::admin_request(Method::POST, &format!("/api/polls/{}/close", id), Value::Null);
+            assert_eq!(send(&app, close).await.0, StatusCode::OK);
+        }
+        let newest = test_util::create_poll(&app, json!({ "title": "Q3", "options": ["BTC", "ETH"] })).await;
+
+        let status_of = |id: Uuid| {
+            let request = test_util::request(Method::GET, &format!("/api/polls/{}", id), Value::Null);
+            let app = app.clone();
+            async move { send(&app, request).await.0 }
+        };
+        assert_eq!(status_of(oldest).await, StatusCode::NOT_FOUND);
+        assert_eq!(status_of(older).await, StatusCode::OK);
+        assert_eq!(status_of(newest).await, StatusCode::OK);
+    }
+
+    #[sqlx::test]
+    async fn unknown_fields_are_rejected_only_in_strict_mode(pool: PgPool) {
+        let body = json!({ "title": "Best coin", "options": ["BTC", "ETH"], "colour": "blue" });
+
+        let strict = test_util::app(test_util::state(pool.clone(), test_util::config(&[("STRICT_JSON", "true")])));
+        let (status, error) = send(&strict, test_util::admin_request(Method::POST, "/api/polls", body.clone())).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(error["message"].as_str().unwrap().contains("colour"), "{}", error);
+
+        let lenient = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&lenient, body).await;
+        let uri = format!("/api/polls/{}/vote", id);
+        let vote = json!({ "option_index": 0, "comment": "to the moon" });
+        assert_eq!(send(&lenient, test_util::request(Method::POST, &uri, vote.clone())).await.0, StatusCode::NO_CONTENT);
+
+        let (status, error) = send(&strict, test_util::request(Method::POST, &uri, vote)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(error["message"].as_str().unwrap().contains("comment"), "{}", error);
+    }
+
+    #[sqlx::test]
+    async fn velocity_counts_recent_votes(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool.clone(), test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+        let uri = format!("/api/polls/{}/velocity?window_seconds=60", id);
+
+        let (status, quiet) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(status, StatusCode::OK, "{}", quiet);
+        assert_eq!(quiet["votes_in_window"], 0);
+        assert_eq!(quiet["votes_per_minute"], 0.0);
+        assert_eq!(quiet["moving_average_per_minute"], 0.0);
+
+        // Three votes in the last minute, two more within five minutes, one older
+        for (voter, seconds_ago) in [("a", 10), ("b", 20), ("c", 30), ("d", 150), ("e", 240), ("f", 600)] {
+            sqlx::query!(
+                "INSERT INTO votes (poll_id, option_index, voter_ip_hash, created_at) VALUES ($1, 0, $2, $3)",
+                id,
+                voter,
+                Utc::now() - Duration::seconds(seconds_ago)
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let (_, busy) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(busy["window_seconds"], 60);
+        assert_eq!(busy["votes_in_window"], 3);
+        assert_eq!(busy["votes_per_minute"], 3.0);
+        assert_eq!(busy["moving_average_per_minute"], 1.0);
+    }
+
+    #[sqlx::test]
+    async fn recorded_votes_answer_without_a_body(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+
+        let (status, headers, body) = test_util::send_raw(&app, vote_from("198.51.100.1", id, 0)).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(body.is_empty());
+        assert!(!headers.contains_key(header::CONTENT_TYPE));
+    }
+
+    #[sqlx::test]
+    async fn blank_user_agents_are_rejected_only_when_required(pool: PgPool) {
+        let vars = [("REQUIRE_USER_AGENT", "true"), ("USER_AGENT_BLOCKLIST", "curl")];
+        let strict = test_util::app(test_util::state(pool.clone(), test_util::config(&vars)));
+        let lenient = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&lenient, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+        let with_agent = |ip: &str, agent: &str| {
+            let mut request = vote_from(ip, id, 0);
+            request.headers_mut().insert(header::USER_AGENT, agent.parse().unwrap());
+            request
+        };
+
+        let (status, error) = send(&strict, with_agent("198.51.100.1", " ")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["message"], "Missing User-Agent");
+        let (status, error) = send(&strict, with_agent("198.51.100.1", "curl/8.5.0")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["message"], "User-Agent not allowed");
+
+        assert_eq!(send(&lenient, with_agent("198.51.100.1", " ")).await.0, StatusCode::NO_CONTENT);
+    }
+
+    #[sqlx::test]
+    async fn option_count_matches_the_tally(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+        for (ip, option_index) in [("198.51.100.1", 1), ("198.51.100.2", 1), ("198.51.100.3", 0)] {
+            assert_eq!(send(&app, vote_from(ip, id, option_index)).await.0, StatusCode::NO_CONTENT);
+        }
+
+        let uri = format!("/api/polls/{}/options/1/count", id);
+        let (status, count) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(status, StatusCode::OK, "{}", count);
+        assert_eq!(count, json!({ "index": 1, "label": "ETH", "count": 2 }));
+
+        let uri = format!("/api/polls/{}/options/2/count", id);
+        assert_eq!(send(&app, test_util::request(Method::GET, &uri, Value::Null)).await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    async fn previews_add_one_vote_without_storing_it(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool.clone(), test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+        assert_eq!(send(&app, vote_from("198.51.100.1", id, 0)).await.0, StatusCode::NO_CONTENT);
+
+        let uri = format!("/api/polls/{}/preview-vote", id);
+        let (status, preview) = send(&app, test_util::request(Method::POST, &uri, json!({ "option_index": 0 }))).await;
+        assert_eq!(status, StatusCode::OK, "{}", preview);
+        assert_eq!(preview["results"][0]["count"], 2);
+        assert_eq!(preview["results"][1]["count"], 0);
+        assert_eq!(preview["total_votes"], 2);
+
+        let stored = sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM votes WHERE poll_id = $1"#, id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, 1);
+        let uri = format!("/api/polls/{}/results", id);
+        let (_, results) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(results["results"][0]["count"], 1);
+
+        let uri = format!("/api/polls/{}/preview-vote", id);
+        let invalid = test_util::request(Method::POST, &uri, json!({ "option_index": 2 }));
+        assert_eq!(send(&app, invalid).await.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[sqlx::test]
+    async fn approvals_count_as_votes_of_one_voter(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let body = json!({ "title": "Best coins", "options": ["BTC", "ETH", "SOL"], "voting_mode": "approval" });
+        let id = test_util::create_poll(&app, body).await;
+        let uri = format!("/api/polls/{}/vote", id);
+        let approvals = test_util::request(Method::POST, &uri, json!({ "approvals": [0, 2] }));
+        assert_eq!(send(&app, approvals).await.0, StatusCode::NO_CONTENT);
+
+        let uri = format!("/api/polls/{}/stats", id);
+        let (status, stats) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(status, StatusCode::OK, "{}", stats);
+        assert_eq!(stats["total_votes"], 2);
+        assert_eq!(stats["unique_voters"], 1);
+    }
+
+    #[sqlx::test]
+    async fn polls_filter_by_locale(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let french = json!({ "title": "Meilleure crypto", "options": ["BTC", "ETH"], "locale": "fr-FR" });
+        let id = test_util::create_poll(&app, french).await;
+        test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"], "locale": "en-US" })).await;
+
+        let (status, page) = send(&app, test_util::request(Method::GET, "/api/polls?locale=fr-FR", Value::Null)).await;
+        assert_eq!(status, StatusCode::OK, "{}", page);
+        assert_eq!(page["total"], 1);
+        assert_eq!(page["polls"][0]["id"], id.to_string());
+        assert_eq!(page["polls"][0]["locale"], "fr-FR");
+
+        let (status, _) = send(&app, test_util::request(Method::GET, "/api/polls?locale=fr_FR!", Value::Null)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let malformed = json!({ "title": "Q", "options": ["BTC", "ETH"], "locale": "not a tag" });
+        let (status, _) = send(&app, test_util::admin_request(Method::POST, "/api/polls", malformed)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[sqlx::test]
+    async fn replay_follows_casting_order_without_voters(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH", "SOL"] })).await;
+        for (ip, option_index) in [("198.51.100.1", 2), ("198.51.100.2", 0), ("198.51.100.3", 1)] {
+            assert_eq!(send(&app, vote_from(ip, id, option_index)).await.0, StatusCode::NO_CONTENT);
+        }
+
+        let uri = format!("/api/polls/{}/replay", id);
+        let (status, replay) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(status, StatusCode::OK, "{}", replay);
+        let votes = replay["votes"].as_array().unwrap();
+        let order: Vec<i64> = votes.iter().map(|v| v["option_index"].as_i64().unwrap()).collect();
+        assert_eq!(order, [2, 0, 1]);
+        for vote in votes {
+            let mut fields: Vec<&str> = vote.as_object().unwrap().keys().map(String::as_str).collect();
+            fields.sort_unstable();
+            assert_eq!(fields, ["option_index", "replay_offset_ms", "voted_at"]);
+        }
+    }
+
+    #[sqlx::test]
+    async fn only_indices_of_the_poll_options_are_accepted(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+
+        let (status, error) = send(&app, vote_from("198.51.100.1", id, 2)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["message"], "Invalid option index");
+        assert_eq!(send(&app, vote_from("198.51.100.1", id, 0)).await.0, StatusCode::NO_CONTENT);
+        assert_eq!(send(&app, vote_from("198.51.100.2", id, 1)).await.0, StatusCode::NO_CONTENT);
+    }
+
+    #[sqlx::test]
+    async fn bundle_sections_agree(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH", "SOL"] })).await;
+        for (ip, option_index) in [("198.51.100.1", 1), ("198.51.100.2", 1), ("198.51.100.3", 0)] {
+            assert_eq!(send(&app, vote_from(ip, id, option_index)).await.0, StatusCode::NO_CONTENT);
+        }
+
+        let uri = format!("/api/polls/{}/bundle", id);
+        let (status, bundle) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(status, StatusCode::OK, "{}", bundle);
+        assert_eq!(bundle["poll"]["id"], id.to_string());
+        assert_eq!(bundle["results"]["poll_id"], id.to_string());
+
+        let counts: Vec<i64> =
+            bundle["results"]["results"].as_array().unwrap().iter().map(|r| r["count"].as_i64().unwrap()).collect();
+        assert_eq!(counts, [1, 2, 0]);
+        let mut per_minute = vec![0; 3];
+        for bucket in bundle["timeseries"].as_array().unwrap() {
+            for (total, count) in per_minute.iter_mut().zip(bucket["counts"].as_array().unwrap()) {
+                *total += count.as_i64().unwrap();
+            }
+        }
+        assert_eq!(per_minute, counts);
+
+        assert_eq!(bundle["winner"]["option_index"], 1);
+        assert_eq!(bundle["winner"]["count"], 2);
+        assert_eq!(bundle["winner"]["provisional"], true);
+        assert!(!bundle.to_string().contains("voter_ip_hash"));
+    }
+
+    #[sqlx::test]
+    async fn exposition_has_one_line_per_option(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH", "S\"OL"] })).await;
+        let vote = test_util::request(Method::POST, &format!("/api/polls/{}/vote", id), json!({ "option_index": 0 }));
+        assert_eq!(send(&app, vote).await.0, StatusCode::NO_CONTENT);
+
+        let uri = format!("/api/polls/{}/results.prom", id);
+        let (status, _, body) = test_util::send_raw(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(status, StatusCode::OK);
+        let body = String::from_utf8(body).unwrap();
+        let lines: Vec<&str> = body.lines().filter(|line| !line.starts_with('#')).collect();
+        assert_eq!(
+            lines,
+            [
+                format!(r#"poll_option_votes{{poll_id="{}",option="BTC"}} 1"#, id),
+                format!(r#"poll_option_votes{{poll_id="{}",option="ETH"}} 0"#, id),
+                format!(r#"poll_option_votes{{poll_id="{}",option="S\"OL"}} 0"#, id),
+            ]
+        );
+    }
+
+    #[sqlx::test]
+    async fn exposition_of_an_unknown_poll_is_404(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let uri = format!("/api/polls/{}/results.prom", Uuid::new_v4());
+        assert_eq!(send(&app, test_util::request(Method::GET, &uri, Value::Null)).await.0, StatusCode::NOT_FOUND);
+    }
+
+    fn keyed_vote(poll_id: Uuid, option_index: i32, key: &str) -> Request<Body> {
+        let mut request =
+            test_util::request(Method::POST, &format!("/api/polls/{}/vote", poll_id), json!({ "option_index": option_index }));
+        request.headers_mut().insert("idempotency-key", key.parse().unwrap());
+        request
+    }
+
+    #[sqlx::test]
+    async fn retried_vote_with_the_same_key_succeeds_once(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool.clone(), test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+
+        assert_eq!(send(&app, keyed_vote(id, 1, "retry-1")).await.0, StatusCode::NO_CONTENT);
+        assert_eq!(send(&app, keyed_vote(id, 1, "retry-1")).await.0, StatusCode::NO_CONTENT);
+        let rows = sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM votes WHERE poll_id = $1"#, id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, 1);
+    }
+
+    #[sqlx::test]
+    async fn reused_key_with_another_ballot_is_422(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+
+        assert_eq!(send(&app, keyed_vote(id, 0, "retry-1")).await.0, StatusCode::NO_CONTENT);
+        let (status, body) = send(&app, keyed_vote(id, 1, "retry-1")).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"], "unprocessable");
+    }
+
+    #[sqlx::test]
+    async fn keys_are_scoped_to_the_voter(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool.clone(), test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Best coin", "options": ["BTC", "ETH"] })).await;
+
+        assert_eq!(send(&app, keyed_vote(id, 0, "retry-1")).await.0, StatusCode::NO_CONTENT);
+        let mut other_voter = keyed_vote(id, 0, "retry-1");
+        other_voter.headers_mut().insert("x-forwarded-for", "198.51.100.1".parse().unwrap());
+        assert_eq!(send(&app, other_voter).await.0, StatusCode::NO_CONTENT);
+        let rows = sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM votes WHERE poll_id = $1"#, id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, 2);
+    }
+
+    #[sqlx::test]
+    async fn clone_copies_options_but_not_identity_or_votes(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[])));
+        let id = test_util::create_poll(&app, json!({ "title": "Weekly", "options": ["BTC", "ETH"] })).await;
+        let vote = test_util::request(Method::POST, &format!("/api/polls/{}/vote", id), json!({ "option_index": 0 }));
+        assert_eq!(send(&app, vote).await.0, StatusCode::NO_CONTENT);
+        let (_, source) = send(&app, test_util::request(Method::GET, &format!("/api/polls/{}", id), Value::Null)).await;
+
+        let request = test_util::admin_request(Method::POST, &format!("/api/polls/{}/clone", id), Value::Null);
+        let (status, clone) = send(&app, request).await;
+        assert_eq!(status, StatusCode::OK, "{}", clone);
+        assert_eq!(clone["options"], source["options"]);
+        assert_eq!(clone["title"], "Weekly (copy)");
+        assert_ne!(clone["id"], source["id"]);
+        assert_ne!(clone["created_at"], source["created_at"]);
+        assert_ne!(clone["expires_at"], source["expires_at"]);
+
+        let uri = format!("/api/polls/{}/results", clone["id"].as_str().unwrap());
+        let (_, results) = send(&app, test_util::request(Method::GET, &uri, Value::Null)).await;
+        assert_eq!(results["total_votes"], 0, "{}", results);
+    }
+
+    #[sqlx::test]
+    async fn clone_duration_is_capped_at_the_maximum(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool.clone(), test_util::config(&[])));
+        let body = json!({ "title": "Weekly", "options": ["BTC", "ETH"], "expires_in_minutes": 600 });
+        let id = test_util::create_poll(&app, body).await;
+
+        let config = test_util::config(&[("POLL_MAX_DURATION_MINUTES", "60"), ("POLL_DEFAULT_DURATION_MINUTES", "30")]);
+        let app = test_util::app(test_util::state(pool, config));
+        let request = test_util::admin_request(Method::POST, &format!("/api/polls/{}/clone", id), Value::Null);
+        let (status, clone) = send(&app, request).await;
+        assert_eq!(status, StatusCode::OK, "{}", clone);
+        let expires_at: DateTime<Utc> = clone["expires_at"].as_str().unwrap().parse().unwrap();
+        assert!(expires_at <= Utc::now() + Duration::minutes(60));
+    }
+
+    #[test]
+    fn prometheus_label_values_are_escaped() {
+        assert_eq!(escape_label_value(r#"a\b"c"#), r#"a\\b\"c"#);
+        assert_eq!(escape_label_value("line\nbreak"), "line\\nbreak");
+    }
+
+    #[test]
+    fn margin_of_a_60_40_split() {
+        let margin = winner_margin(&[60, 40]).unwrap();
+        assert_eq!(margin, WinnerMargin { votes: 20, percentage: 20.0 });
+    }
+
+    #[test]
+    fn margin_is_measured_against_the_runner_up() {
+        let margin = winner_margin(&[10, 50, 40]).unwrap();
+        assert_eq!(margin.votes, 10);
+        assert_eq!(margin.percentage, 10.0);
+    }
+
+    #[test]
+    fn no_margin_without_votes_or_a_runner_up() {
+        assert_eq!(winner_margin(&[0, 0]), None);
+        assert_eq!(winner_margin(&[7]), None);
+        assert_eq!(winner_margin(&[]), None);
+    }
+
+    #[test]
+    fn race_is_close_up_to_the_threshold() {
+        let margin = winner_margin(&[52, 48]).unwrap();
+        assert!(is_close_race(Some(&margin), 5.0));
+        assert!(is_close_race(Some(&margin), 4.0));
+        assert!(!is_close_race(Some(&margin), 3.9));
+        assert!(!is_close_race(None, 5.0));
+    }
+
+    #[test]
+    fn language_tags_are_checked_structurally() {
+        for tag in ["fr", "fr-FR", "zh-Hant-TW", "es-419"] {
+            assert!(is_valid_language_tag(tag), "{}", tag);
+        }
+        for tag in ["", "f", "fr_FR", "fr-", "1a-FR", "fr-toolongsubtag"] {
+            assert!(!is_valid_language_tag(tag), "{}", tag);
+        }
+    }
+
+    #[test]
+    fn tie_is_always_close() {
+        let margin = winner_margin(&[30, 30, 5]).unwrap();
+        assert_eq!(margin.votes, 0);
+        assert!(is_close_race(Some(&margin), 0.0));
+    }
+
+    #[sqlx::test]
+    async fn authors_are_capped_at_their_open_polls(pool: PgPool) {
+        let app = test_util::app(test_util::state(pool, test_util::config(&[("MAX_ACTIVE_POLLS_PER_AUTHOR", "2")])));
+        let poll = |author: &str| json!({ "title": "Best coin", "options": ["BTC", "ETH"], "created_by": author });
+
+        test_util::create_poll(&app, poll("alice")).await;
+        let second = test_util::create_poll(&app, poll("alice")).await;
+        let (status, body) = send(&app, test_util::admin_request(Method::POST, "/api/polls", poll("alice"))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"], "too_many_active_polls");
+
+        // Other authors are unaffected, and closing a poll frees a slot
+        test_util::create_poll(&app, poll("bob")).await;
+        let close = test_util::admin_request(Method::POST, &format!("/api/polls/{}/close", second), Value::Null);
+        assert_eq!(send(&app, close).await.0, StatusCode::OK);
+        test_util::create_poll(&app, poll("alice")).await;
+    }
+}
@@ -1,47 +1,201 @@
 // src/main.rs
+use axum::extract::DefaultBodyLimit;
 use axum::http::HeaderValue;
-use axum::Router;
-use axum_server::Server;
+use axum_server::{Handle, Server};
 use dotenvy::dotenv;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::timeout::TimeoutLayer;
+use tracing::info;
+mod archive;
+mod binance;
+mod config;
 mod db;
+mod error;
+mod extract;
+mod features;
 mod handlers;
+mod metrics;
+mod middleware;
 mod models;
+mod notify;
+mod openapi;
+mod price_cache;
+mod price_stream;
+mod purge;
+mod rate_limit;
 mod routes;
+mod runoff;
+mod shuffle;
+mod state;
+#[cfg(test)]
+mod test_util;
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
     tracing_subscriber::fmt::init(); // Initialize tracing for logging
 
-    let pool = db::create_pool().await.expect("Failed to create pool");
+    let config = Arc::new(config::Config::from_env());
+    metrics::init();
+    // Migrations and the purge task must not be cut short by the request deadline
+    let maintenance_pool = db::create_maintenance_pool(&config)
+        .await
+        .expect("Failed to create maintenance pool");
 
     // Run migrations first
     println!("Starting database migrations...");
     sqlx::migrate!("./migrations")
-        .run(&pool)
+        .run(&maintenance_pool)
         .await
         .expect("Failed to run migrations");
     println!("Migrations completed successfully!");
 
-    let cors_origin = std::env::var("CORS_ORIGIN")
-        .expect("CORS_ORIGIN must be set")
-        .parse::<HeaderValue>()
-        .expect("Invalid CORS_ORIGIN format");
+    let pool = db::create_pool(&config).await.expect("Failed to create pool");
+
+    assert!(!config.poll_purge_interval.is_zero(), "POLL_PURGE_INTERVAL_MINUTES must be positive");
+    assert!(config.poll_default_duration_minutes > 0, "POLL_DEFAULT_DURATION_MINUTES must be positive");
+    assert!(
+        config.poll_max_duration_minutes.is_none_or(|max| config.poll_default_duration_minutes <= max),
+        "POLL_DEFAULT_DURATION_MINUTES must not exceed POLL_MAX_DURATION_MINUTES"
+    );
+    let archive = config
+        .archive_storage
+        .clone()
+        .map(|storage| Arc::new(archive::S3Store::new(storage)) as Arc<dyn archive::ObjectStore>);
+    tokio::spawn(purge::run(maintenance_pool, config.poll_retention, config.poll_purge_interval, archive));
 
     let cors = CorsLayer::new()
-        .allow_origin(AllowOrigin::exact(cors_origin))
+        .allow_origin(cors_origin())
         .allow_methods(tower_http::cors::Any)
         .allow_headers(tower_http::cors::Any);
 
-    let app = routes::create_router().with_state(pool).layer(cors);
+    let timeout = TimeoutLayer::new(config.request_timeout);
+
+    let global_limit = config.global_rate_limit_rps.map(|rps| {
+        assert!(rps > 0.0, "GLOBAL_RATE_LIMIT_RPS must be positive");
+        // Below one unit of burst even an empty bucket would shed every request
+        let burst = config.global_rate_limit_burst.unwrap_or(rps).max(1.0);
+        Arc::new(rate_limit::LeakyBucket::new(rps, burst))
+    });
+
+    let notifier = config
+        .vote_notify_webhook_url
+        .clone()
+        .map(|url| Arc::new(notify::WebhookNotifier::new(url)) as Arc<dyn notify::Notifier>);
+
+    let rate_limits = Arc::new(rate_limit::RateLimits::new(config.vote_rate_limit, config.poll_create_rate_limit));
+    tokio::spawn(rate_limit::prune(rate_limits.clone(), rate_limit::PRUNE_INTERVAL));
+
+    let binance = binance::BinanceClient::new(config.binance_api_url.clone());
+
+    let prices = price_stream::PriceStream::new(config.price_stream_buffer);
+    tokio::spawn(price_stream::run(pool.clone(), prices.clone(), config.binance_ws_url.clone()));
+
+    let version_gate = axum::middleware::from_fn_with_state(config.clone(), middleware::client_version_gate);
+
+    // In-flight requests can't outlive the request timeout, so use it as the drain deadline
+    let shutdown_grace = config.request_timeout;
+    let addr = config.bind_addr;
+    let body_limit = DefaultBodyLimit::max(config.max_body_bytes);
+    let features = features::FeatureFlags::new(&config.disabled_features);
+    let state = state::AppState { pool: pool.clone(), config, notifier, binance, prices, rate_limits, features };
+    let mut app = routes::create_router()
+        .with_state(state.clone())
+        .layer(body_limit)
+        .layer(timeout)
+        .layer(version_gate);
+
+    // Shed excess load before it reaches routing or the database
+    if let Some(bucket) = global_limit {
+        app = app.layer(axum::middleware::from_fn_with_state(bucket, middleware::global_rate_limit));
+    }
+    let app = app
+        .merge(routes::health_router().with_state(state.clone()))
+        .merge(routes::docs_router().with_state(state.clone()))
+        .layer(cors)
+        .merge(routes::metrics_router().with_state(state))
+        .layer(axum::middleware::from_fn(middleware::request_id));
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
     println!("Listening on {}", addr);
 
+    let handle = Handle::new();
+    tokio::spawn(shutdown_signal(handle.clone(), shutdown_grace));
+
     Server::bind(addr)
-        .serve(app.into_make_service())
+        .handle(handle)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .unwrap();
+
+    // Requests have drained; let pending writes finish and release connections
+    pool.close().await;
+    info!("Server stopped");
+}
+
+/// Builds the allowed origins from `CORS_ORIGIN`, a comma-separated list such as
+/// `https://app.example.com,https://staging.example.com`. With
+/// `CORS_ALLOW_WILDCARD_SUBDOMAINS=true`, entries like `https://*.example.com`
+/// also match any subdomain of `example.com`.
+fn cors_origin() -> AllowOrigin {
+    let raw = std::env::var("CORS_ORIGIN").expect("CORS_ORIGIN must be set");
+    let allow_wildcards = std::env::var("CORS_ALLOW_WILDCARD_SUBDOMAINS").is_ok_and(|v| v == "true");
+
+    let mut exact = Vec::new();
+    let mut wildcards = Vec::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        if let Some((scheme, domain)) = entry.split_once("://*.") {
+            assert!(allow_wildcards, "Wildcard CORS_ORIGIN entry {} needs CORS_ALLOW_WILDCARD_SUBDOMAINS=true", entry);
+            wildcards.push((format!("{}://", scheme), format!(".{}", domain)));
+        } else {
+            let origin = entry
+                .parse::<HeaderValue>()
+                .unwrap_or_else(|_| panic!("Invalid CORS_ORIGIN entry: {}", entry));
+            exact.push(origin);
+        }
+    }
+    assert!(!exact.is_empty() || !wildcards.is_empty(), "CORS_ORIGIN must list at least one origin");
+
+    if wildcards.is_empty() {
+        return AllowOrigin::list(exact);
+    }
+    AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+        exact.contains(origin)
+            || origin.to_str().is_ok_and(|origin| {
+                wildcards.iter().any(|(scheme, suffix)| {
+                    origin
+                        .strip_prefix(scheme.as_str())
+                        .and_then(|host| host.strip_suffix(suffix.as_str()))
+                        .is_some_and(|sub| !sub.is_empty() && !sub.contains('/'))
+                })
+            })
+    })
+}
+
+/// Waits for SIGINT or SIGTERM, then stops accepting connections and gives
+/// in-flight requests up to `grace` to complete.
+async fn shutdown_signal(handle: Handle, grace: Duration) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("Failed to listen for SIGINT");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    info!("Shutdown signal received, draining in-flight requests...");
+    handle.graceful_shutdown(Some(grace));
 }
\ No newline at end of file
@@ -0,0 +1,83 @@
+// src/metrics.rs
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::sync::LazyLock;
+
+/// Process-wide Prometheus metrics, served at `/metrics`.
+pub struct Metrics {
+    registry: Registry,
+    pub votes_submitted: IntCounter,
+    /// Labelled by `reason`: `expired`, `already_voted` or `invalid_index`.
+    pub votes_rejected: IntCounterVec,
+    pub polls_created: IntCounter,
+    /// Labelled by `outcome`: `success` or `failure`.
+    pub binance_fetches: IntCounterVec,
+    /// Labelled by `method`, matched `route` and `status`.
+    pub request_duration: HistogramVec,
+    /// Requests refused by the global rate limit, which runs before `track_metrics`.
+    pub requests_shed: IntCounter,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Registers every metric; called once at startup so `/metrics` lists them from the start.
+pub fn init() {
+    LazyLock::force(&METRICS);
+}
+
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+        let metrics = Self {
+            votes_submitted: IntCounter::new("poll_votes_submitted_total", "Votes recorded.")
+                .expect("valid metric"),
+            votes_rejected: IntCounterVec::new(
+                Opts::new("poll_votes_rejected_total", "Votes refused, by reason."),
+                &["reason"],
+            )
+            .expect("valid metric"),
+            polls_created: IntCounter::new("poll_polls_created_total", "Polls created, including clones.")
+                .expect("valid metric"),
+            binance_fetches: IntCounterVec::new(
+                Opts::new("poll_binance_fetches_total", "Binance price fetches, by outcome."),
+                &["outcome"],
+            )
+            .expect("valid metric"),
+            request_duration: HistogramVec::new(
+                HistogramOpts::new("poll_http_request_duration_seconds", "HTTP request latency, by route."),
+                &["method", "route", "status"],
+            )
+            .expect("valid metric"),
+            requests_shed: IntCounter::new("poll_requests_shed_total", "Requests shed by the global rate limit.")
+                .expect("valid metric"),
+            registry,
+        };
+
+        let collectors: [Box<dyn prometheus::core::Collector>; 6] = [
+            Box::new(metrics.votes_submitted.clone()),
+            Box::new(metrics.votes_rejected.clone()),
+            Box::new(metrics.polls_created.clone()),
+            Box::new(metrics.binance_fetches.clone()),
+            Box::new(metrics.request_duration.clone()),
+            Box::new(metrics.requests_shed.clone()),
+        ];
+        for collector in collectors {
+            metrics.registry.register(collector).expect("metric registered once");
+        }
+        metrics
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("text encoding never fails");
+        String::from_utf8(buffer).expect("exposition format is UTF-8")
+    }
+}
@@ -0,0 +1,249 @@
+// src/middleware.rs
+use axum::{
+    body::{self, Body},
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, error, field, info, info_span, Instrument};
+use uuid::Uuid;
+
+use crate::config::{parse_version, Config};
+use crate::error::ApiError;
+use crate::metrics::metrics;
+use crate::rate_limit::LeakyBucket;
+
+/// Rejects clients older than `MIN_CLIENT_VERSION` with `426 Upgrade Required`.
+/// Versions compare component-wise, with missing components counting as 0;
+/// a header that doesn't parse is treated as outdated.
+pub async fn client_version_gate(
+    State(config): State<Arc<Config>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(minimum) = &config.min_client_version else {
+        return next.run(request).await;
+    };
+
+    let supported = match request.headers().get("x-client-version") {
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(parse_version)
+            .is_some_and(|version| !is_older(&version, minimum)),
+        None => !config.client_version_required,
+    };
+    if supported {
+        return next.run(request).await;
+    }
+
+    let message = match &config.client_upgrade_url {
+        Some(url) => format!("This client version is no longer supported, please upgrade: {}", url),
+        None => "This client version is no longer supported, please upgrade".to_string(),
+    };
+    ApiError::UpgradeRequired(message).into_response()
+}
+
+fn is_older(version: &[u64], minimum: &[u64]) -> bool {
+    let len = version.len().max(minimum.len());
+    let padded = |v: &[u64]| (0..len).map(|i| v.get(i).copied().unwrap_or(0)).collect::<Vec<_>>();
+    padded(version) < padded(minimum)
+}
+
+/// Sheds requests beyond the process-wide rate with `503` and a `Retry-After`.
+pub async fn global_rate_limit(
+    State(bucket): State<Arc<LeakyBucket>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match bucket.try_acquire() {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            // One line per shed request would flood the logs exactly when overloaded
+            debug!("Global rate limit exceeded; shedding {} {}", request.method(), request.uri().path());
+            metrics().requests_shed.inc();
+            ApiError::Overloaded(retry_after).into_response()
+        }
+    }
+}
+
+/// Records the latency of requests to matched routes, labelled by the route
+/// template (`/api/polls/{id}`) rather than the raw path to bound cardinality.
+pub async fn track_metrics(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    metrics()
+        .request_duration
+        .with_label_values(&[&method, &route, response.status().as_str()])
+        .observe(started.elapsed().as_secs_f64());
+    response
+}
+
+static REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest incoming `X-Request-Id` reused as is; longer ones are replaced.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Tags each request with an ID, taken from a sane incoming `X-Request-Id` or
+/// freshly generated, and runs it inside a `request` span carrying the ID,
+/// method and path so every log line of the request can be correlated. The
+/// status is recorded on completion and the ID echoed in the response.
+pub async fn request_id(request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&REQUEST_ID)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.chars().all(|c| c.is_ascii_graphic()))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let span = info_span!(
+        "request",
+        request_id = %id,
+        method = %request.method(),
+        path = %request.uri().path(),
+        status = field::Empty,
+    );
+
+    let started = Instant::now();
+    let mut response = next.run(request).instrument(span.clone()).await;
+    span.record("status", response.status().as_u16());
+    span.in_scope(|| info!(elapsed_ms = started.elapsed().as_millis() as u64, "request completed"));
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID.clone(), value);
+    }
+    response
+}
+
+/// Re-serializes JSON responses of GET requests with indentation when the
+/// query string contains `pretty=true`. Responses stay compact otherwise.
+pub async fn pretty_json(request: Request, next: Next) -> Response {
+    let wants_pretty = request.method() == Method::GET
+        && request
+            .uri()
+            .query()
+            .is_some_and(|q| q.split('&').any(|pair| pair == "pretty=true"));
+
+    let response = next.run(request).await;
+    if !wants_pretty || !is_json(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to buffer response body: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let pretty = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .and_then(|value| serde_json::to_vec_pretty(&value));
+    match pretty {
+        Ok(pretty) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(pretty))
+        }
+        // Not valid JSON after all; hand back the original bytes untouched
+        Err(_) => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(v: &str) -> Vec<u64> {
+        parse_version(v).unwrap()
+    }
+
+    #[test]
+    fn parses_dotted_versions() {
+        assert_eq!(parse_version("2.4.0"), Some(vec![2, 4, 0]));
+        assert_eq!(parse_version(" 1.10 "), Some(vec![1, 10]));
+        assert_eq!(parse_version("1.x"), None);
+        assert_eq!(parse_version(""), None);
+        assert_eq!(parse_version("1..2"), None);
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert!(is_older(&version("1.9"), &version("1.10")));
+        assert!(!is_older(&version("1.10"), &version("1.9")));
+        assert!(!is_older(&version("2.4.0"), &version("2.4.0")));
+    }
+
+    #[test]
+    fn missing_components_count_as_zero() {
+        assert!(!is_older(&version("2.4"), &version("2.4.0")));
+        assert!(!is_older(&version("2.4.0"), &version("2.4")));
+        assert!(is_older(&version("2"), &version("2.0.1")));
+    }
+
+    #[tokio::test]
+    async fn requests_beyond_the_global_rate_are_shed_and_counted() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        let bucket = Arc::new(LeakyBucket::new(0.001, 1.0));
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(bucket, global_rate_limit));
+        let get_root = || Request::get("/").body(Body::empty()).unwrap();
+
+        let shed_before = metrics().requests_shed.get();
+        assert_eq!(app.clone().oneshot(get_root()).await.unwrap().status(), StatusCode::OK);
+        let response = app.oneshot(get_root()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        assert_eq!(metrics().requests_shed.get(), shed_before + 1);
+    }
+
+    #[tokio::test]
+    async fn json_is_indented_only_on_request() {
+        use axum::{routing::get, Json, Router};
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route("/", get(|| async { Json(serde_json::json!({ "options": ["BTC", "ETH"], "total": 2 })) }))
+            .layer(axum::middleware::from_fn(pretty_json));
+        let body_of = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+                let bytes = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                String::from_utf8(bytes.to_vec()).unwrap()
+            }
+        };
+
+        let compact = body_of("/").await;
+        let pretty = body_of("/?pretty=true").await;
+        assert!(!compact.contains('\n'));
+        assert!(pretty.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap()
+        );
+    }
+}
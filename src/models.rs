@@ -1,34 +1,479 @@
 // src/models.rs
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Debug, sqlx::FromRow, Serialize, Deserialize)]
+use crate::binance::TickerPrice;
+
+#[derive(Debug, sqlx::FromRow, Serialize, Deserialize, ToSchema)]
 pub struct Poll {
     pub id: Uuid,
     pub title: String,
     pub options: Vec<String>,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    pub per_option_threshold: i32,
+    pub locale: Option<String>,
+    /// When the poll was closed early by hand, as opposed to reaching `expires_at`.
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Whether voters may change or retract their vote while the poll is open.
+    pub allow_vote_change: bool,
+    pub voting_mode: VotingMode,
+    /// Author shown for the poll, as given when it was created.
+    pub created_by: Option<String>,
+}
+
+/// How a poll's votes are cast and counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "voting_mode", rename_all = "lowercase")]
+pub enum VotingMode {
+    /// One option per voter.
+    #[default]
+    Single,
+    /// Any number of options per voter, each counted once.
+    Approval,
+    /// Options in order of preference, counted by instant runoff.
+    Ranked,
+}
+
+/// Query parameters of `GET /api/polls`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListPollsParams {
+    /// Polls per page (default 20, clamped to 1..=100).
+    pub limit: Option<i64>,
+    /// Polls to skip from the newest (default 0, negative counts as 0).
+    pub offset: Option<i64>,
+    /// Only list polls attributed to this author.
+    pub created_by: Option<String>,
+    /// Only list polls in this BCP-47 language tag, compared case-insensitively.
+    pub locale: Option<String>,
+}
+
+/// Page of a listing without further filters.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageParams {
+    /// Entries per page (default 20, clamped to 1..=100).
+    pub limit: Option<i64>,
+    /// Entries to skip (default 0, negative counts as 0).
+    pub offset: Option<i64>,
+}
+
+/// A page of polls, newest first, with the total across all pages.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PollsPage {
+    pub polls: Vec<Poll>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// A poll with its expiry state computed against the server clock.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PollWithExpiry {
+    #[serde(flatten)]
+    pub poll: Poll,
+    /// True once the poll is past `expires_at` or was closed by hand.
+    pub is_expired: bool,
+    /// Whole seconds until `expires_at`; 0 once expired.
+    pub seconds_remaining: i64,
 }
 
-#[derive(Debug, sqlx::FromRow)]
+/// A poll as fetched by a voter. With option shuffling enabled it also lists
+/// the options in this voter's order, with the token to vote by position.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PollDetails {
+    #[serde(flatten)]
+    pub poll: Poll,
+    /// Server clock when the poll was read, to correct countdowns for skew.
+    pub server_time: DateTime<Utc>,
+    /// Left out while `OPTION_ORDER_SECRET` is unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shuffled: Option<ShuffledOptions>,
+}
+
+/// One stored vote row, as listed in a poll's audit log. A ballot of an
+/// approval or ranked poll spans one row per chosen option. The contact digest
+/// and the `Idempotency-Key` stay out of the log.
+#[derive(Debug, Serialize, ToSchema)]
 pub struct Vote {
     pub id: Uuid,
     pub poll_id: Uuid,
     pub option_index: i32,
-    pub voter_ip: String,
+    /// Position of the option on its ballot; 1 for single-choice votes.
+    pub rank: i32,
+    /// HMAC of the voter's IP, or `legacy-<id>` for votes cast before
+    /// addresses were hashed or without a resolvable address.
+    pub voter_ip_hash: String,
     pub created_at: DateTime<Utc>,
+    /// Whether the voter left a contact for result notifications.
+    pub has_contact: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreatePoll {
     pub title: String,
     pub options: Vec<String>,
-    pub expires_in_minutes: i64,
+    /// How long the poll runs; give at most one of this and `expires_at`.
+    /// Without either, the server's default duration applies.
+    #[serde(default)]
+    pub expires_in_minutes: Option<i64>,
+    /// Wall-clock time the poll closes, which must lie in the future.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Votes an option needs to be reported as passed; 0 disables the threshold.
+    #[serde(default)]
+    pub per_option_threshold: i32,
+    /// BCP-47 language tag of the poll's content, e.g. `fr-FR`.
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub allow_vote_change: bool,
+    /// Defaults to `single`.
+    #[serde(default)]
+    pub voting_mode: VotingMode,
+    /// Author to attribute the poll to, e.g. a community handle.
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// Record each option's current Binance price, treating options as symbols.
+    #[serde(default)]
+    pub capture_prices: bool,
+    /// Reject options that aren't symbols listed on Binance.
+    #[serde(default)]
+    pub validate_symbols: bool,
+}
+
+/// Optional overrides when cloning a poll; the whole body may be omitted.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ClonePoll {
+    pub title: Option<String>,
+    pub expires_in_minutes: Option<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A ballot. Single-choice polls take `option_index` or `option`, approval
+/// polls `approvals` and ranked polls `ranking`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct VoteRequest {
+    /// The chosen option of a `single` poll.
+    #[serde(default)]
+    pub option_index: Option<i32>,
+    /// Label of the chosen option of a `single` poll, matched ignoring case;
+    /// an alternative to `option_index` that doesn't depend on option order.
+    #[serde(default)]
+    pub option: Option<String>,
+    /// Every approved option of an `approval` poll.
+    #[serde(default)]
+    pub approvals: Option<Vec<i32>>,
+    /// Options of a `ranked` poll, most preferred first; any may be left out.
+    #[serde(default)]
+    pub ranking: Option<Vec<i32>>,
+    /// `shuffled.order_token` of the poll fetch; when present, the submitted
+    /// indices are positions in that shuffled list rather than real option indices.
+    #[serde(default)]
+    pub order_token: Option<String>,
+    /// Optional email address to send a vote confirmation to. Only its hash is stored.
+    #[serde(default)]
+    pub contact: Option<String>,
+}
+
+/// Authoritative server clock, so clients can correct countdowns for skew.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ServerTime {
+    pub server_time: DateTime<Utc>,
+}
+
+/// Readiness of the service, as reported by `/health`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthStatus {
+    pub status: &'static str,
+    /// Reachability of upstream services; only present with `READINESS_CHECKS_BINANCE`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<UpstreamStatus>,
+}
+
+/// Reachability of the services the API depends on.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UpstreamStatus {
+    /// `ok` or `unreachable`.
+    pub binance: &'static str,
+}
+
+/// Tally of a poll; every option is listed, including those without votes.
+/// Approval polls count every approval and ranked polls first preferences.
+/// Carries the poll's metadata so one request can render a results screen.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PollResults {
+    pub poll_id: Uuid,
+    pub title: String,
+    pub options: Vec<String>,
+    pub expires_at: DateTime<Utc>,
+    pub voting_mode: VotingMode,
+    /// Number of ballots cast.
+    pub total_votes: i64,
+    pub results: Vec<OptionResult>,
+    /// Instant-runoff count of a ranked poll; left out for other modes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounds: Option<Vec<RunoffRound>>,
+}
+
+/// A page of an author's polls with their results, newest first, with the
+/// total across all pages.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthorResultsPage {
+    pub polls: Vec<PollResults>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// One round of an instant-runoff count.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RunoffRound {
+    /// Ballots counting towards each option this round, indexed like the poll's options.
+    pub counts: Vec<i64>,
+    /// Options eliminated at the end of the round; empty in the final round.
+    pub eliminated: Vec<i32>,
+    /// Ballots whose ranked options have all been eliminated.
+    pub exhausted: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OptionResult {
+    pub option_index: i32,
+    pub option_label: String,
+    pub count: i64,
+    /// Share of ballots counting this option; 0.0 for every option while there are no votes.
+    pub percentage: f64,
+    /// Whether the option reached the poll's `per_option_threshold`; `None`
+    /// when the poll has no threshold.
+    pub passed: Option<bool>,
+    /// Binance price of the option's symbol when the poll opened, if captured.
+    pub starting_price: Option<String>,
+}
+
+/// Outcome of a poll. With a unique winner its index and label are set; on a
+/// tie they are `None` and `tied_indices` lists the options sharing the lead.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PollWinner {
+    /// True while the poll still takes votes, so the outcome may change.
+    pub provisional: bool,
+    /// True when nobody has voted; no winner or tie is reported then.
+    pub no_votes: bool,
+    pub option_index: Option<i32>,
+    pub option_label: Option<String>,
+    /// Votes of the leading option(s).
+    pub count: i64,
+    pub tie: bool,
+    pub tied_indices: Vec<i32>,
+    /// Lead over the runner-up; absent without votes or with a single option.
+    pub margin: Option<WinnerMargin>,
+    /// Whether the lead is within `CLOSE_RACE_THRESHOLD_PERCENT`; always true on a tie.
+    pub is_close: bool,
+    /// Whether the leading count reached the poll's `per_option_threshold`;
+    /// `None` when the poll has no threshold.
+    pub passed: Option<bool>,
+}
+
+/// A poll's full public state in one document.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PollBundle {
+    pub poll: Poll,
+    pub results: PollResults,
+    /// Votes per minute, only for minutes in which someone voted.
+    pub timeseries: Vec<TimeseriesBucket>,
+    pub winner: PollWinner,
+}
+
+/// Votes cast in one minute, counted like the results.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimeseriesBucket {
+    pub start: DateTime<Utc>,
+    /// Votes per option in that minute, indexed like the poll's options.
+    pub counts: Vec<i64>,
+}
+
+/// How far the leading option is ahead of the runner-up.
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct WinnerMargin {
+    /// Votes the leader has over the runner-up; 0 on a tie.
+    pub votes: i64,
+    /// The same lead in percentage points of all counted votes.
+    pub percentage: f64,
+}
+
+/// Vote count of a single option, for embeddable counters.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OptionCount {
+    pub index: i32,
+    pub label: String,
+    pub count: i64,
+}
+
+/// Lightweight results: whole percentages aligned with the poll's option order.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CompactResults {
+    pub percentages: Vec<i64>,
+    pub total_votes: i64,
+}
+
+/// A hypothetical vote whose effect on the results should be previewed.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PreviewVote {
     pub option_index: i32,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReplayParams {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// Playback speed multiplier applied to `replay_offset_ms` (default 1).
+    pub speed: Option<f64>,
+}
+
+/// One vote of a replay, without any voter identity.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayVote {
+    pub option_index: i32,
+    pub voted_at: DateTime<Utc>,
+    /// When to play this vote back, in milliseconds after the poll opened, at the requested speed.
+    pub replay_offset_ms: i64,
+}
+
+/// Query parameters of a poll's vote audit log.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VotesParams {
+    /// Votes per page (default 100, clamped to 1..=1000).
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// Only votes cast at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only votes cast before this time.
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// A page of a poll's votes in casting order, with the total matching the filters.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VotesPage {
+    pub votes: Vec<Vote>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayPage {
+    pub votes: Vec<ReplayVote>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// A poll's options in the order shown to one voter, with the token that maps
+/// positions back to the real option indices when voting.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShuffledOptions {
+    pub options: Vec<String>,
+    pub order_token: String,
+}
+
+/// Vote totals of a poll.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PollStats {
+    pub total_votes: i64,
+    /// Distinct voter identities; every legacy vote counts as its own voter.
+    pub unique_voters: i64,
+    /// When the earliest and latest votes were cast; `null` before the first vote.
+    pub first_vote_at: Option<DateTime<Utc>>,
+    pub last_vote_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VelocityParams {
+    pub window_seconds: Option<i64>,
+}
+
+/// New choice for an existing vote.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChangeVote {
+    pub option_index: i32,
+    /// Same meaning as in `VoteRequest`.
+    #[serde(default)]
+    pub order_token: Option<String>,
+}
+
+/// Query parameters of the live results stream.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ResultsStreamParams {
+    /// Seconds between checks for new votes (default 2, clamped to 1..=60).
+    pub interval_secs: Option<u64>,
+}
+
+/// Query parameters of `GET /api/coins`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CoinsParams {
+    /// Comma-separated symbols to keep, e.g. `BTCUSDT,ETHUSDT`.
+    pub symbols: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Price of a coin, live or, while Binance is unreachable, from the last
+/// successful fetch.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CoinPrice {
+    #[serde(flatten)]
+    pub ticker: TickerPrice,
+    /// True when Binance could not be reached and the price is cached.
+    pub stale: bool,
+    /// When the price was fetched from Binance.
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Query parameters of `GET /api/prices/ws`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PriceStreamParams {
+    /// Comma-separated symbols to follow, e.g. `BTCUSDT,ETHUSDT`.
+    pub symbols: String,
+}
+
+/// Sent on `/api/prices/ws` after the client fell too far behind and missed
+/// updates: the latest known prices of its symbols, replacing what it has.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PriceResync {
+    /// Always true; tells this message apart from a `TickerPrice`.
+    pub resync: bool,
+    /// Updates dropped since the last message.
+    pub skipped: u64,
+    pub prices: Vec<TickerPrice>,
+}
+
+/// Recent voting rate of a poll.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PollVelocity {
+    pub window_seconds: i64,
+    pub votes_in_window: i64,
+    pub votes_per_minute: f64,
+    /// Rate over the last five windows, smoothing out single-window spikes.
+    pub moving_average_per_minute: f64,
+}
+
+/// Whether each optional feature is on, as reported by `/api/admin/features`.
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct FeatureStates {
+    pub prices: bool,
+    pub streaming: bool,
+    pub webhooks: bool,
+}
+
+/// Body of `PUT /api/admin/features/{name}`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FeatureToggle {
+    pub enabled: bool,
+}
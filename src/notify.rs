@@ -0,0 +1,56 @@
+// src/notify.rs
+use serde::Serialize;
+use std::time::Duration;
+use tracing::error;
+use uuid::Uuid;
+
+/// Details of a recorded vote sent to a voter who left a contact address.
+#[derive(Debug, Clone, Serialize)]
+pub struct VoteConfirmation {
+    pub contact: String,
+    pub poll_id: Uuid,
+    pub poll_title: String,
+    pub option: String,
+}
+
+/// Delivers vote confirmations. Implementations must not block the caller;
+/// delivery failures are theirs to log.
+pub trait Notifier: Send + Sync {
+    fn notify(&self, confirmation: VoteConfirmation);
+}
+
+/// Longest a webhook may take to accept a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest a webhook delivery may take overall, so hung deliveries don't pile up.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Posts each confirmation as JSON to a webhook (`VOTE_NOTIFY_WEBHOOK_URL`),
+/// leaving the actual email delivery to the receiving service.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to build webhook HTTP client");
+        Self { client, url }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, confirmation: VoteConfirmation) {
+        let request = self.client.post(&self.url).json(&confirmation);
+        tokio::spawn(async move {
+            let result = request.send().await.and_then(|r| r.error_for_status());
+            if let Err(e) = result {
+                error!("Failed to send vote confirmation for poll {}: {}", confirmation.poll_id, e);
+            }
+        });
+    }
+}
@@ -0,0 +1,98 @@
+// src/openapi.rs
+use std::sync::LazyLock;
+
+use axum::Json;
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use utoipa::openapi::OpenApi as OpenApiSpec;
+use utoipa::{Modify, OpenApi};
+
+use crate::binance::TickerPrice;
+use crate::error::ErrorBody;
+use crate::handlers;
+use crate::models::{
+    AuthorResultsPage, ChangeVote, ClonePoll, CoinPrice, CompactResults, CreatePoll, FeatureStates, FeatureToggle,
+    HealthStatus, OptionCount, OptionResult, Poll, PollBundle, PollDetails, PollResults, PollStats, PollVelocity,
+    PollWinner, PollWithExpiry, PollsPage, PreviewVote, PriceResync, ReplayPage, ReplayVote, RunoffRound, ServerTime,
+    ShuffledOptions, TimeseriesBucket, UpstreamStatus, Vote, VoteRequest, VotesPage, VotingMode, WinnerMargin,
+};
+
+/// OpenAPI description of every route, served at `/api-docs/openapi.json`.
+/// Besides the documented responses, any `/api` route may answer `426` for an
+/// outdated client, `503` from the global rate limit, `408` on timeout and
+/// `413` for a body over `MAX_BODY_BYTES`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Crypto Poll API"),
+    paths(
+        handlers::list_polls,
+        handlers::create_poll,
+        handlers::get_poll,
+        handlers::submit_vote,
+        handlers::change_vote,
+        handlers::retract_vote,
+        handlers::get_results,
+        handlers::get_current_poll,
+        handlers::submit_vote_current,
+        handlers::get_current_results,
+        handlers::get_author_results,
+        handlers::close_poll,
+        handlers::clone_poll,
+        handlers::clone_current_poll,
+        handlers::preview_vote,
+        handlers::stream_results,
+        handlers::get_results_csv,
+        handlers::get_results_prom,
+        handlers::get_compact_results,
+        handlers::get_option_count,
+        handlers::get_replay,
+        handlers::list_votes,
+        handlers::list_related_polls,
+        handlers::get_stats,
+        handlers::get_winner,
+        handlers::get_bundle,
+        handlers::get_velocity,
+        handlers::list_features,
+        handlers::set_feature,
+        handlers::get_server_time,
+        handlers::get_coins,
+        handlers::get_coin,
+        handlers::stream_prices,
+        handlers::health,
+        handlers::live,
+        handlers::get_metrics,
+    ),
+    components(schemas(
+        AuthorResultsPage, ChangeVote, ClonePoll, CoinPrice, CompactResults, CreatePoll, ErrorBody, FeatureStates,
+        FeatureToggle, HealthStatus, OptionCount, OptionResult, Poll, PollBundle, PollDetails, PollResults, PollStats,
+        PollVelocity, PollWinner, PollWithExpiry, PollsPage, PreviewVote, PriceResync, ReplayPage, ReplayVote,
+        RunoffRound, ServerTime, ShuffledOptions, TickerPrice, TimeseriesBucket, UpstreamStatus, Vote, VoteRequest,
+        VotesPage, VotingMode, WinnerMargin,
+    )),
+    modifiers(&AdminTokenScheme),
+    tags(
+        (name = "polls", description = "Creating, listing and managing polls"),
+        (name = "votes", description = "Casting, changing and retracting votes"),
+        (name = "results", description = "Tallies, exports and statistics"),
+        (name = "coins", description = "Live Binance prices"),
+        (name = "service", description = "Health, metrics and server time"),
+    )
+)]
+struct ApiDoc;
+
+/// Registers the `ADMIN_TOKEN` bearer scheme referenced by the admin routes.
+struct AdminTokenScheme;
+
+impl Modify for AdminTokenScheme {
+    fn modify(&self, openapi: &mut OpenApiSpec) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme("admin_token", SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)));
+        }
+    }
+}
+
+static SPEC: LazyLock<OpenApiSpec> = LazyLock::new(ApiDoc::openapi);
+
+/// Serves the OpenAPI document of the API.
+pub async fn openapi_json() -> Json<OpenApiSpec> {
+    Json(SPEC.clone())
+}
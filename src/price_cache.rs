@@ -0,0 +1,123 @@
+// src/price_cache.rs
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::binance::TickerPrice;
+use crate::models::CoinPrice;
+
+/// Shortest time between two cache writes of the same symbol. `/api/coins`
+/// fetches every listed symbol, so writing on each request would rewrite
+/// thousands of rows per call.
+const MIN_STORE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// When each symbol was last written by this process.
+static LAST_STORED: LazyLock<Mutex<HashMap<String, Instant>>> = LazyLock::new(Default::default);
+
+/// Records `prices` as the latest known price of their symbols, skipping
+/// symbols written less than `MIN_STORE_INTERVAL` ago.
+pub async fn store(pool: &PgPool, prices: &[TickerPrice]) -> Result<(), sqlx::Error> {
+    let due = {
+        let mut last_stored = LAST_STORED.lock().unwrap_or_else(|e| e.into_inner());
+        due_for_store(&mut last_stored, prices, Instant::now())
+    };
+    if due.is_empty() {
+        return Ok(());
+    }
+    let (symbols, values): (Vec<String>, Vec<String>) =
+        due.iter().map(|p| (p.symbol.clone(), p.price.clone())).unzip();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO price_cache (symbol, price, fetched_at)
+        SELECT symbol, price, NOW()
+        FROM UNNEST($1::TEXT[], $2::TEXT[]) AS t(symbol, price)
+        ON CONFLICT (symbol) DO UPDATE SET price = EXCLUDED.price, fetched_at = EXCLUDED.fetched_at
+        "#,
+        &symbols as &[String],
+        &values as &[String]
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Cached prices of `symbols`, or of every cached symbol when `None`, marked stale.
+pub async fn load(pool: &PgPool, symbols: Option<&[String]>) -> Result<Vec<CoinPrice>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT symbol, price, fetched_at
+        FROM price_cache
+        WHERE $1::TEXT[] IS NULL OR symbol = ANY($1)
+        ORDER BY symbol
+        "#,
+        symbols as Option<&[String]>
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| CoinPrice {
+            ticker: TickerPrice { symbol: r.symbol, price: r.price },
+            stale: true,
+            fetched_at: r.fetched_at,
+        })
+        .collect())
+}
+
+/// Picks the prices whose symbol wasn't written within `MIN_STORE_INTERVAL`
+/// of `now`, and marks them as written.
+fn due_for_store<'a>(
+    last_stored: &mut HashMap<String, Instant>,
+    prices: &'a [TickerPrice],
+    now: Instant,
+) -> Vec<&'a TickerPrice> {
+    prices
+        .iter()
+        .filter(|price| {
+            let recent = last_stored
+                .get(&price.symbol)
+                .is_some_and(|&at| now.duration_since(at) < MIN_STORE_INTERVAL);
+            if !recent {
+                last_stored.insert(price.symbol.clone(), now);
+            }
+            !recent
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(symbol: &str) -> TickerPrice {
+        TickerPrice { symbol: symbol.to_string(), price: "1.0".to_string() }
+    }
+
+    fn symbols(due: &[&TickerPrice]) -> Vec<String> {
+        due.iter().map(|p| p.symbol.clone()).collect()
+    }
+
+    #[test]
+    fn recently_written_symbols_are_skipped() {
+        let mut last_stored = HashMap::new();
+        let start = Instant::now();
+        let first = [price("BTCUSDT"), price("ETHUSDT")];
+        assert_eq!(symbols(&due_for_store(&mut last_stored, &first, start)), ["BTCUSDT", "ETHUSDT"]);
+
+        let second = [price("BTCUSDT"), price("SOLUSDT")];
+        let later = start + Duration::from_secs(1);
+        assert_eq!(symbols(&due_for_store(&mut last_stored, &second, later)), ["SOLUSDT"]);
+    }
+
+    #[test]
+    fn symbols_are_written_again_after_the_interval() {
+        let mut last_stored = HashMap::new();
+        let start = Instant::now();
+        let prices = [price("BTCUSDT")];
+        due_for_store(&mut last_stored, &prices, start);
+        assert_eq!(due_for_store(&mut last_stored, &prices, start + MIN_STORE_INTERVAL).len(), 1);
+    }
+}
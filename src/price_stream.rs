@@ -0,0 +1,236 @@
+// src/price_stream.rs
+use futures_util::StreamExt;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tracing::{info, warn};
+
+use crate::binance::TickerPrice;
+use crate::models::PriceResync;
+
+/// How often the symbols of active polls are re-read to adjust the subscription.
+const SYMBOL_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// Pause before the first reconnect after the upstream socket drops; doubled
+/// on every failed attempt up to `MAX_BACKOFF`.
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Most streams Binance accepts on one connection.
+const MAX_STREAMS: usize = 1024;
+
+/// Next message for a price subscriber.
+#[derive(Debug)]
+pub enum Delivery {
+    Price(TickerPrice),
+    /// The subscriber fell behind; it gets the current prices instead of what it missed.
+    Resync(PriceResync),
+}
+
+/// Live Binance prices for the symbols of active polls, kept current by
+/// [`run`]. Clones share the same prices and updates.
+#[derive(Clone)]
+pub struct PriceStream {
+    prices: Arc<RwLock<HashMap<String, String>>>,
+    updates: broadcast::Sender<TickerPrice>,
+}
+
+impl PriceStream {
+    /// Buffers up to `buffer` updates per subscriber; slower subscribers skip
+    /// ahead and are told how many updates they missed.
+    pub fn new(buffer: usize) -> Self {
+        Self {
+            prices: Arc::new(RwLock::new(HashMap::new())),
+            updates: broadcast::channel(buffer).0,
+        }
+    }
+
+    /// Latest known prices of `symbols`; symbols without a price yet are left out.
+    pub fn snapshot(&self, symbols: &HashSet<String>) -> Vec<TickerPrice> {
+        let prices = self.prices.read().expect("price map lock poisoned");
+        symbols
+            .iter()
+            .filter_map(|symbol| {
+                let price = prices.get(symbol)?;
+                Some(TickerPrice { symbol: symbol.clone(), price: price.clone() })
+            })
+            .collect()
+    }
+
+    /// Receives every price update from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<TickerPrice> {
+        self.updates.subscribe()
+    }
+
+    /// Waits for the next update of one of `symbols` on `updates`. A receiver
+    /// that lagged more than the buffer behind gets a snapshot of `symbols`
+    /// instead, so missed updates are never silently dropped. `None` once the
+    /// stream is gone.
+    pub async fn next_for(
+        &self,
+        updates: &mut broadcast::Receiver<TickerPrice>,
+        symbols: &HashSet<String>,
+    ) -> Option<Delivery> {
+        loop {
+            match updates.recv().await {
+                Ok(price) if symbols.contains(&price.symbol) => return Some(Delivery::Price(price)),
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    let prices = self.snapshot(symbols);
+                    return Some(Delivery::Resync(PriceResync { resync: true, skipped, prices }));
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    fn publish(&self, price: TickerPrice) {
+        self.prices
+            .write()
+            .expect("price map lock poisoned")
+            .insert(price.symbol.clone(), price.price.clone());
+        // Nobody listening is fine; the map still holds the price
+        let _ = self.updates.send(price);
+    }
+}
+
+/// Frame of a Binance combined stream: `{"stream": "btcusdt@miniTicker", "data": {...}}`.
+#[derive(Deserialize)]
+struct StreamFrame {
+    data: MiniTicker,
+}
+
+#[derive(Deserialize)]
+struct MiniTicker {
+    #[serde(rename = "s")]
+    symbol: String,
+    /// Last price.
+    #[serde(rename = "c")]
+    close: String,
+}
+
+/// Feeds `stream` from Binance's websocket API at `ws_url` for as long as the
+/// process runs. Only options of polls still taking votes are subscribed, and
+/// no connection is held while there are none. A dropped connection is
+/// retried with exponential backoff.
+pub async fn run(pool: PgPool, stream: PriceStream, ws_url: String) {
+    let base_url = ws_url.trim_end_matches('/').to_string();
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        let symbols = match active_symbols(&pool).await {
+            Ok(symbols) if !symbols.is_empty() => symbols,
+            Ok(_) => {
+                tokio::time::sleep(SYMBOL_REFRESH_INTERVAL).await;
+                continue;
+            }
+            Err(e) => {
+                warn!("Failed to read the symbols of active polls: {}", e);
+                tokio::time::sleep(SYMBOL_REFRESH_INTERVAL).await;
+                continue;
+            }
+        };
+
+        // Ok means the active symbols changed, so resubscribe straight away
+        if let Err(e) = follow(&pool, &stream, &base_url, &symbols, &mut backoff).await {
+            warn!("Binance price stream dropped, reconnecting in {:?}: {}", backoff, e);
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+}
+
+/// Publishes the mini tickers of `symbols` until the connection fails or the
+/// symbols of active polls no longer match `symbols`.
+async fn follow(
+    pool: &PgPool,
+    stream: &PriceStream,
+    base_url: &str,
+    symbols: &BTreeSet<String>,
+    backoff: &mut Duration,
+) -> Result<(), WsError> {
+    let streams = symbols
+        .iter()
+        .map(|symbol| format!("{}@miniTicker", symbol.to_lowercase()))
+        .collect::<Vec<_>>()
+        .join("/");
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("{}/stream?streams={}", base_url, streams)).await?;
+    info!("Streaming Binance prices for {} symbols", symbols.len());
+    *backoff = MIN_BACKOFF;
+
+    let mut refresh = tokio::time::interval(SYMBOL_REFRESH_INTERVAL);
+    refresh.tick().await;
+    loop {
+        tokio::select! {
+            message = socket.next() => match message {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<StreamFrame>(&text) {
+                    Ok(frame) => stream.publish(TickerPrice { symbol: frame.data.symbol, price: frame.data.close }),
+                    Err(e) => warn!("Unexpected Binance stream message: {}", e),
+                },
+                Some(Ok(Message::Close(_))) | None => return Err(WsError::ConnectionClosed),
+                // Pings are answered by tungstenite itself
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+            },
+            _ = refresh.tick() => {
+                if active_symbols(pool).await.is_ok_and(|current| current != *symbols) {
+                    let _ = socket.close(None).await;
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Options of polls still taking votes that could be Binance symbols
+/// (non-empty ASCII letters and digits), uppercased.
+async fn active_symbols(pool: &PgPool) -> Result<BTreeSet<String>, sqlx::Error> {
+    let options = sqlx::query_scalar!(
+        r#"
+        SELECT DISTINCT UPPER(TRIM(o.opt)) as "symbol!"
+        FROM polls CROSS JOIN LATERAL UNNEST(options) AS o(opt)
+        WHERE closed_at IS NULL AND expires_at > NOW()
+        ORDER BY 1
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(options
+        .into_iter()
+        .filter(|symbol| !symbol.is_empty() && symbol.chars().all(|c| c.is_ascii_alphanumeric()))
+        .take(MAX_STREAMS)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(symbol: &str, price: &str) -> TickerPrice {
+        TickerPrice { symbol: symbol.to_string(), price: price.to_string() }
+    }
+
+    #[tokio::test]
+    async fn lagging_subscribers_are_resynced_with_a_snapshot() {
+        let stream = PriceStream::new(2);
+        let symbols = HashSet::from(["BTCUSDT".to_string()]);
+        let mut updates = stream.subscribe();
+        for value in ["1", "2", "3", "4"] {
+            stream.publish(price("BTCUSDT", value));
+        }
+        stream.publish(price("ETHUSDT", "5"));
+
+        match stream.next_for(&mut updates, &symbols).await {
+            Some(Delivery::Resync(resync)) => {
+                assert!(resync.resync);
+                assert_eq!(resync.skipped, 3);
+                let prices: Vec<(&str, &str)> =
+                    resync.prices.iter().map(|p| (p.symbol.as_str(), p.price.as_str())).collect();
+                assert_eq!(prices, [("BTCUSDT", "4")]);
+            }
+            other => panic!("expected a resync, got {:?}", other),
+        }
+    }
+}
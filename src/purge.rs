@@ -0,0 +1,192 @@
+// src/purge.rs
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::archive::{self, ArchivedVote, ObjectStore, PollArchive};
+use crate::handlers;
+use crate::models::{Poll, VotingMode};
+
+/// Deletes polls (and their votes) that expired more than `retention` ago,
+/// once every `interval`. With an `archive` store, each poll is uploaded
+/// first and only deleted once its upload succeeded. Failures are logged and
+/// retried on the next run.
+pub async fn run(pool: PgPool, retention: Duration, interval: StdDuration, archive: Option<Arc<dyn ObjectStore>>) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match purge_expired_polls(&pool, retention, archive.as_deref()).await {
+            Ok((0, _)) => {}
+            Ok((polls, votes)) => info!("Purged {} expired polls and {} votes", polls, votes),
+            Err(e) => error!("Failed to purge expired polls: {}", e),
+        }
+    }
+}
+
+/// Returns the number of polls and votes removed.
+async fn purge_expired_polls(
+    pool: &PgPool,
+    retention: Duration,
+    archive: Option<&dyn ObjectStore>,
+) -> Result<(i64, i64), sqlx::Error> {
+    let cutoff = Utc::now() - retention;
+    let archived = match archive {
+        Some(store) => Some(archive_expired_polls(pool, cutoff, store).await?),
+        None => None,
+    };
+
+    // Votes would cascade anyway; deleting them explicitly lets us count them
+    let purged = sqlx::query!(
+        r#"
+        WITH doomed AS (
+            SELECT id FROM polls WHERE expires_at < $1 AND ($2::UUID[] IS NULL OR id = ANY($2))
+        ),
+        deleted_votes AS (
+            DELETE FROM votes WHERE poll_id IN (SELECT id FROM doomed) RETURNING 1
+        ),
+        deleted_polls AS (
+            DELETE FROM polls WHERE id IN (SELECT id FROM doomed) RETURNING 1
+        )
+        SELECT
+            (SELECT COUNT(*) FROM deleted_polls) as "polls!",
+            (SELECT COUNT(*) FROM deleted_votes) as "votes!"
+        "#,
+        cutoff,
+        archived.as_deref() as Option<&[Uuid]>
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok((purged.polls, purged.votes))
+}
+
+/// Uploads every poll that expired before `cutoff` to `store`, returning the
+/// ids of those that made it. The others are kept for the next run.
+async fn archive_expired_polls(
+    pool: &PgPool,
+    cutoff: chrono::DateTime<Utc>,
+    store: &dyn ObjectStore,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let polls = sqlx::query_as!(
+        Poll,
+        r#"
+        SELECT id, title, options, expires_at, created_at, per_option_threshold, locale, closed_at, allow_vote_change,
+            voting_mode as "voting_mode: VotingMode", created_by
+        FROM polls
+        WHERE expires_at < $1
+        "#,
+        cutoff
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut archived = Vec::with_capacity(polls.len());
+    for poll in polls {
+        let poll_id = poll.id;
+        // fetch_results logs its own failures
+        let Ok(results) = handlers::fetch_results(pool, &poll).await else {
+            continue;
+        };
+        let votes = sqlx::query_as!(
+            ArchivedVote,
+            r#"
+            SELECT option_index, rank, created_at
+            FROM votes
+            WHERE poll_id = $1
+            ORDER BY created_at, rank
+            "#,
+            poll_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        match archive::upload(store, &PollArchive { poll, results, votes }).await {
+            Ok(()) => archived.push(poll_id),
+            Err(e) => error!("Failed to archive poll {}, keeping it until the next purge: {}", poll_id, e),
+        }
+    }
+    Ok(archived)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Store that keeps objects in memory and fails the first `failures` puts.
+    #[derive(Default)]
+    struct MemoryStore {
+        objects: Mutex<HashMap<String, Vec<u8>>>,
+        failures: Mutex<u32>,
+    }
+
+    impl ObjectStore for MemoryStore {
+        fn put<'a>(&'a self, key: &'a str, body: Vec<u8>) -> archive::PutFuture<'a> {
+            Box::pin(async move {
+                let mut failures = self.failures.lock().unwrap();
+                if *failures > 0 {
+                    *failures -= 1;
+                    return Err("store unavailable".into());
+                }
+                self.objects.lock().unwrap().insert(key.to_string(), body);
+                Ok(())
+            })
+        }
+    }
+
+    async fn insert_expired_poll(pool: &PgPool) -> Uuid {
+        let poll_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO polls (title, options, expires_at)
+            VALUES ('Old poll', ARRAY['BTC', 'ETH'], NOW() - INTERVAL '60 days')
+            RETURNING id
+            "#
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO votes (poll_id, option_index, voter_ip_hash) VALUES ($1, 1, 'voter-hash')",
+            poll_id
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        poll_id
+    }
+
+    #[sqlx::test]
+    async fn purged_polls_are_archived_first(pool: PgPool) {
+        let poll_id = insert_expired_poll(&pool).await;
+        let store = MemoryStore { failures: Mutex::new(1), ..Default::default() };
+
+        let purged = purge_expired_polls(&pool, Duration::days(30), Some(&store)).await.unwrap();
+        assert_eq!(purged, (1, 1));
+
+        let objects = store.objects.lock().unwrap();
+        let archive: Value = serde_json::from_slice(&objects[&archive::object_key(poll_id)]).unwrap();
+        assert_eq!(archive["poll"]["title"], "Old poll");
+        assert_eq!(archive["results"]["results"][1]["count"], 1);
+        assert_eq!(archive["votes"][0]["option_index"], 1);
+        assert!(!archive.to_string().contains("voter-hash"));
+    }
+
+    #[sqlx::test]
+    async fn polls_stay_when_their_archive_fails(pool: PgPool) {
+        let poll_id = insert_expired_poll(&pool).await;
+        let store = MemoryStore { failures: Mutex::new(u32::MAX), ..Default::default() };
+
+        let purged = purge_expired_polls(&pool, Duration::days(30), Some(&store)).await.unwrap();
+        assert_eq!(purged, (0, 0));
+        let remaining = sqlx::query_scalar!("SELECT COUNT(*) FROM polls WHERE id = $1", poll_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, Some(1));
+    }
+}
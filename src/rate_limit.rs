@@ -0,0 +1,199 @@
+// src/rate_limit.rs
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Leaky bucket shared by all requests of the process. Each request adds one
+/// unit; the bucket drains at `rate` units per second and holds at most `burst`.
+#[derive(Debug)]
+pub struct LeakyBucket {
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    level: f64,
+    last_leak: Instant,
+}
+
+impl LeakyBucket {
+    pub fn new(rate: f64, burst: f64) -> Self {
+        Self {
+            rate,
+            burst,
+            state: Mutex::new(BucketState { level: 0.0, last_leak: Instant::now() }),
+        }
+    }
+
+    /// Admits a request, or returns how long the caller should wait before retrying.
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.admit(self.rate, self.burst, Instant::now())
+    }
+}
+
+impl BucketState {
+    fn admit(&mut self, rate: f64, burst: f64, now: Instant) -> Result<(), Duration> {
+        self.drain(rate, now);
+        if self.level + 1.0 > burst {
+            let overflow = self.level + 1.0 - burst;
+            return Err(Duration::from_secs_f64(overflow / rate));
+        }
+        self.level += 1.0;
+        Ok(())
+    }
+
+    fn drain(&mut self, rate: f64, now: Instant) {
+        let leaked = now.duration_since(self.last_leak).as_secs_f64() * rate;
+        self.level = (self.level - leaked).max(0.0);
+        self.last_leak = now;
+    }
+}
+
+/// How often [`prune`] drops the buckets of keys that went quiet.
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// One bucket per key (a client IP), with the same semantics as [`LeakyBucket`].
+#[derive(Debug)]
+pub struct KeyedLimiter {
+    rate: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<String, BucketState>>,
+}
+
+impl KeyedLimiter {
+    pub fn new(rate: f64, burst: f64) -> Self {
+        Self { rate, burst, buckets: Mutex::new(HashMap::new()) }
+    }
+
+    /// Admits a request for `key`, or returns how long it should wait before retrying.
+    pub fn try_acquire(&self, key: &str) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        buckets
+            .entry(key.to_string())
+            .or_insert(BucketState { level: 0.0, last_leak: now })
+            .admit(self.rate, self.burst, now)
+    }
+
+    /// Drops fully drained buckets, which behave exactly like missing ones.
+    fn prune(&self) {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        buckets.retain(|_, bucket| {
+            bucket.drain(self.rate, now);
+            bucket.level > 0.0
+        });
+    }
+}
+
+/// Per-client limits on write endpoints; `None` when disabled.
+#[derive(Debug)]
+pub struct RateLimits {
+    pub votes: Option<KeyedLimiter>,
+    pub poll_creation: Option<KeyedLimiter>,
+}
+
+impl RateLimits {
+    /// Builds a limiter for each configured `(rate, burst)`; a rate of 0 disables it.
+    pub fn new(votes: (f64, f64), poll_creation: (f64, f64)) -> Self {
+        let limiter = |(rate, burst): (f64, f64)| (rate > 0.0).then(|| KeyedLimiter::new(rate, burst.max(1.0)));
+        Self { votes: limiter(votes), poll_creation: limiter(poll_creation) }
+    }
+}
+
+/// Prunes the per-client buckets of `limits` once every `interval`, so the
+/// maps only hold clients seen recently and requests never pay for a scan.
+pub async fn prune(limits: Arc<RateLimits>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        for limiter in [&limits.votes, &limits.poll_creation].into_iter().flatten() {
+            limiter.prune();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_bucket(now: Instant) -> BucketState {
+        BucketState { level: 0.0, last_leak: now }
+    }
+
+    #[test]
+    fn admits_up_to_burst_then_rejects() {
+        let now = Instant::now();
+        let mut bucket = empty_bucket(now);
+        for _ in 0..3 {
+            assert!(bucket.admit(1.0, 3.0, now).is_ok());
+        }
+        assert!(bucket.admit(1.0, 3.0, now).is_err());
+    }
+
+    #[test]
+    fn retry_after_is_time_to_drain_one_unit() {
+        let now = Instant::now();
+        let mut bucket = empty_bucket(now);
+        bucket.admit(2.0, 1.0, now).unwrap();
+        let wait = bucket.admit(2.0, 1.0, now).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn drains_over_time() {
+        let start = Instant::now();
+        let mut bucket = empty_bucket(start);
+        bucket.admit(1.0, 1.0, start).unwrap();
+        assert!(bucket.admit(1.0, 1.0, start + Duration::from_millis(500)).is_err());
+        assert!(bucket.admit(1.0, 1.0, start + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn slow_rate_with_unit_burst_admits_first_request() {
+        let now = Instant::now();
+        let mut bucket = empty_bucket(now);
+        assert!(bucket.admit(0.5, 1.0, now).is_ok());
+        assert_eq!(bucket.admit(0.5, 1.0, now).unwrap_err(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn keyed_limiter_tracks_each_key_separately() {
+        let limiter = KeyedLimiter::new(0.001, 1.0);
+        assert!(limiter.try_acquire("10.0.0.1").is_ok());
+        assert!(limiter.try_acquire("10.0.0.1").is_err());
+        assert!(limiter.try_acquire("10.0.0.2").is_ok());
+    }
+
+    #[test]
+    fn pruning_drops_only_drained_buckets() {
+        let fast = KeyedLimiter::new(1000.0, 1.0);
+        let slow = KeyedLimiter::new(0.001, 1.0);
+        fast.try_acquire("10.0.0.1").unwrap();
+        slow.try_acquire("10.0.0.2").unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+
+        fast.prune();
+        slow.prune();
+        assert!(fast.buckets.lock().unwrap().is_empty());
+        assert!(slow.buckets.lock().unwrap().contains_key("10.0.0.2"));
+    }
+
+    #[test]
+    fn rate_of_zero_disables_a_limiter() {
+        let limits = RateLimits::new((0.0, 5.0), (1.0, 3.0));
+        assert!(limits.votes.is_none());
+        assert!(limits.poll_creation.is_some());
+    }
+
+    #[test]
+    fn leaky_bucket_sheds_beyond_burst() {
+        let bucket = LeakyBucket::new(0.001, 2.0);
+        assert!(bucket.try_acquire().is_ok());
+        assert!(bucket.try_acquire().is_ok());
+        assert!(bucket.try_acquire().is_err());
+    }
+}
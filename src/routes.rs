@@ -1,13 +1,119 @@
 // src/routes.rs
-use axum::{Router, routing::{get, post}};
+use axum::{Router, middleware, routing::{get, post, put}};
+use crate::error::ApiError;
 use crate::handlers;
-use http::StatusCode;
+use crate::middleware::{pretty_json, track_metrics};
+use crate::openapi;
+use crate::state::AppState;
 
-pub fn create_router() -> Router<sqlx::PgPool> {
+pub fn create_router() -> Router<AppState> {
     Router::new()
-        .route("/api/polls", post(handlers::create_poll))
+        .route("/api/polls", get(handlers::list_polls).post(handlers::create_poll))
+        .route("/api/polls/{id}", get(handlers::get_poll))
+        .route(
+            "/api/polls/{id}/vote",
+            post(handlers::submit_vote).put(handlers::change_vote).delete(handlers::retract_vote),
+        )
+        .route("/api/polls/{id}/results", get(handlers::get_results))
+        .route("/api/polls/{id}/votes", get(handlers::list_votes))
+        // Aliases acting on the most recently created poll
         .route("/api/polls/current", get(handlers::get_current_poll))
-        .route("/api/votes", post(handlers::submit_vote))
-        .route("/api/results", get(handlers::get_results))
-        .fallback(get(|| async { (StatusCode::NOT_FOUND, "Route not found".to_string()) }))
-}
\ No newline at end of file
+        .route("/api/polls/current/clone", post(handlers::clone_current_poll))
+        .route("/api/votes", post(handlers::submit_vote_current))
+        .route("/api/results", get(handlers::get_current_results))
+        .route("/api/polls/{id}/close", post(handlers::close_poll))
+        .route("/api/polls/{id}/clone", post(handlers::clone_poll))
+        .route("/api/polls/{id}/preview-vote", post(handlers::preview_vote))
+        .route("/api/polls/{id}/results/stream", get(handlers::stream_results))
+        .route("/api/polls/{id}/results.csv", get(handlers::get_results_csv))
+        .route("/api/polls/{id}/results.prom", get(handlers::get_results_prom))
+        .route("/api/polls/{id}/results/compact", get(handlers::get_compact_results))
+        .route("/api/polls/{id}/options/{index}/count", get(handlers::get_option_count))
+        .route("/api/polls/{id}/replay", get(handlers::get_replay))
+        .route("/api/polls/{id}/related", get(handlers::list_related_polls))
+        .route("/api/polls/{id}/stats", get(handlers::get_stats))
+        .route("/api/polls/{id}/winner", get(handlers::get_winner))
+        .route("/api/polls/{id}/bundle", get(handlers::get_bundle))
+        .route("/api/polls/{id}/velocity", get(handlers::get_velocity))
+        .route("/api/authors/{author}/results", get(handlers::get_author_results))
+        .route("/api/admin/features", get(handlers::list_features))
+        .route("/api/admin/features/{name}", put(handlers::set_feature))
+        .route("/api/time", get(handlers::get_server_time))
+        .route("/api/coins", get(handlers::get_coins))
+        .route("/api/coins/{symbol}", get(handlers::get_coin))
+        .route("/api/prices/ws", get(handlers::stream_prices))
+        .fallback(route_not_found)
+        .method_not_allowed_fallback(method_not_allowed)
+        .route_layer(middleware::from_fn(track_metrics))
+        .layer(middleware::from_fn(pretty_json))
+}
+
+/// Health probes for load balancers and orchestrators. Merged outside the
+/// API middleware so probes never hit the client version gate or rate limit.
+pub fn health_router() -> Router<AppState> {
+    Router::new()
+        .route("/health", get(handlers::health))
+        .route("/live", get(handlers::live))
+}
+
+/// Machine-readable API description, outside the client version gate so
+/// codegen tools can always fetch it.
+pub fn docs_router() -> Router<AppState> {
+    Router::new().route("/api-docs/openapi.json", get(openapi::openapi_json))
+}
+
+/// Prometheus scrape endpoint, merged outside the CORS layer.
+pub fn metrics_router() -> Router<AppState> {
+    Router::new().route("/metrics", get(handlers::get_metrics))
+}
+
+/// JSON `404` for paths that match no route, whatever the method.
+async fn route_not_found() -> ApiError {
+    ApiError::RouteNotFound
+}
+
+/// JSON `405` for known paths hit with the wrong method; axum adds the `Allow` header.
+async fn method_not_allowed() -> ApiError {
+    ApiError::MethodNotAllowed
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::extract::DefaultBodyLimit;
+    use axum::http::{header, Method, StatusCode};
+    use serde_json::{json, Value};
+
+    use crate::test_util::{self, send};
+
+    #[tokio::test]
+    async fn unknown_route_is_a_json_404() {
+        let app = test_util::app(test_util::state(test_util::lazy_pool(), test_util::config(&[])));
+        let (status, body) = send(&app, test_util::request(Method::GET, "/api/nope", Value::Null)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "route_not_found");
+    }
+
+    #[tokio::test]
+    async fn wrong_method_is_a_json_405_with_allow() {
+        let app = test_util::app(test_util::state(test_util::lazy_pool(), test_util::config(&[])));
+        let request = test_util::request(Method::PATCH, "/api/polls", Value::Null);
+        let (status, headers, body) = test_util::send_raw(&app, request).await;
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+        let allow = headers[header::ALLOW].to_str().unwrap();
+        assert!(allow.contains("GET") && allow.contains("POST"), "{}", allow);
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "method_not_allowed");
+    }
+
+    #[tokio::test]
+    async fn oversized_body_is_a_json_413() {
+        let state = test_util::state(test_util::lazy_pool(), test_util::config(&[]));
+        let app = test_util::app(state).layer(DefaultBodyLimit::max(64));
+        let body = json!({ "option_index": 0, "contact": "x".repeat(1024) });
+        let uri = format!("/api/polls/{}/vote", uuid::Uuid::new_v4());
+
+        let (status, body) = send(&app, test_util::request(Method::POST, &uri, body)).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"], "payload_too_large");
+    }
+}
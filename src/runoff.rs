@@ -0,0 +1,106 @@
+// src/runoff.rs
+use crate::models::RunoffRound;
+
+/// Counts ranked ballots by instant runoff. Each round credits every ballot to
+/// its most preferred option still in the race. Unless one option holds a
+/// majority of the ballots still counting, the options with the fewest votes
+/// are eliminated together and their ballots move on to the next preference.
+///
+/// Counting stops at a majority, when a single option is left, or when all
+/// remaining options are tied; the last round then shows that tie.
+/// `ballots` hold option indices, most preferred first.
+pub fn instant_runoff(option_count: usize, ballots: &[Vec<usize>]) -> Vec<RunoffRound> {
+    let mut continuing = vec![true; option_count];
+    let mut rounds = Vec::new();
+
+    loop {
+        let mut counts = vec![0i64; option_count];
+        let mut exhausted = 0;
+        for ballot in ballots {
+            match ballot.iter().find(|&&i| continuing.get(i) == Some(&true)) {
+                Some(&i) => counts[i] += 1,
+                None => exhausted += 1,
+            }
+        }
+
+        let counting = ballots.len() as i64 - exhausted;
+        let remaining: Vec<usize> = (0..option_count).filter(|&i| continuing[i]).collect();
+        let fewest = remaining.iter().map(|&i| counts[i]).min().unwrap_or(0);
+        let trailing: Vec<usize> = remaining.iter().copied().filter(|&i| counts[i] == fewest).collect();
+
+        // Eliminating every remaining option would leave no winner to report
+        let finished = counts.iter().any(|&c| c * 2 > counting)
+            || remaining.len() <= 1
+            || trailing.len() == remaining.len();
+        let eliminated = if finished { Vec::new() } else { trailing };
+        for &i in &eliminated {
+            continuing[i] = false;
+        }
+
+        rounds.push(RunoffRound {
+            counts,
+            eliminated: eliminated.iter().map(|&i| i as i32).collect(),
+            exhausted,
+        });
+        if finished {
+            return rounds;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn majority_wins_in_the_first_round() {
+        let ballots = vec![vec![0, 1], vec![0], vec![1, 0]];
+        let rounds = instant_runoff(2, &ballots);
+        assert_eq!(rounds.len(), 1);
+        assert_eq!(rounds[0].counts, vec![2, 1]);
+        assert!(rounds[0].eliminated.is_empty());
+    }
+
+    #[test]
+    fn eliminated_ballots_move_to_the_next_preference() {
+        let ballots = vec![vec![0], vec![0], vec![1], vec![1], vec![2, 1]];
+        let rounds = instant_runoff(3, &ballots);
+        assert_eq!(rounds.len(), 2);
+        assert_eq!(rounds[0].counts, vec![2, 2, 1]);
+        assert_eq!(rounds[0].eliminated, vec![2]);
+        assert_eq!(rounds[1].counts, vec![2, 3, 0]);
+        assert!(rounds[1].eliminated.is_empty());
+    }
+
+    #[test]
+    fn exhausted_ballots_stop_counting() {
+        let ballots = vec![vec![0], vec![0], vec![1], vec![1], vec![2]];
+        let rounds = instant_runoff(3, &ballots);
+        assert_eq!(rounds[1].exhausted, 1);
+        assert_eq!(rounds[1].counts, vec![2, 2, 0]);
+    }
+
+    #[test]
+    fn counting_stops_at_a_full_tie() {
+        let ballots = vec![vec![0], vec![1]];
+        let rounds = instant_runoff(2, &ballots);
+        assert_eq!(rounds.len(), 1);
+        assert!(rounds[0].eliminated.is_empty());
+    }
+
+    #[test]
+    fn trailing_options_are_eliminated_together() {
+        let ballots = vec![vec![0], vec![0], vec![0], vec![1], vec![2, 0], vec![3, 1]];
+        let rounds = instant_runoff(4, &ballots);
+        assert_eq!(rounds[0].eliminated, vec![1, 2, 3]);
+        assert_eq!(rounds[1].counts, vec![4, 0, 0, 0]);
+    }
+
+    #[test]
+    fn no_ballots_yield_one_empty_round() {
+        let rounds = instant_runoff(3, &[]);
+        assert_eq!(rounds.len(), 1);
+        assert_eq!(rounds[0].counts, vec![0, 0, 0]);
+        assert_eq!(rounds[0].exhausted, 0);
+    }
+}
@@ -0,0 +1,119 @@
+// src/shuffle.rs
+use hmac::{Hmac, Mac};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use sha2::Sha256;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Returns a permutation of `0..option_count` that is stable for a given
+/// poll and voter, so the same voter sees the same order on every fetch.
+/// `order[position]` is the real option index shown at `position`.
+pub fn shuffled_order(secret: &str, poll_id: Uuid, voter: &str, option_count: usize) -> Vec<usize> {
+    let seed: [u8; 32] = mac(secret, format!("seed:{}:{}", poll_id, voter).as_bytes())
+        .finalize()
+        .into_bytes()
+        .into();
+
+    let mut order: Vec<usize> = (0..option_count).collect();
+    order.shuffle(&mut StdRng::from_seed(seed));
+    order
+}
+
+/// Encodes `order` as a token of the form `2-0-1.<hex signature>`, binding it to the poll.
+pub fn sign_order(secret: &str, poll_id: Uuid, order: &[usize]) -> String {
+    let encoded = order.iter().map(usize::to_string).collect::<Vec<_>>().join("-");
+    let signature = mac(secret, signed_payload(poll_id, &encoded).as_bytes())
+        .finalize()
+        .into_bytes();
+    let hex: String = signature.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}.{}", encoded, hex)
+}
+
+/// Checks a token produced by [`sign_order`] for `poll_id` and returns the
+/// order it carries, or `None` if it was tampered with or is for another poll.
+pub fn verify_order(secret: &str, poll_id: Uuid, token: &str) -> Option<Vec<usize>> {
+    let (encoded, hex) = token.split_once('.')?;
+    let signature = decode_hex(hex)?;
+    mac(secret, signed_payload(poll_id, encoded).as_bytes())
+        .verify_slice(&signature)
+        .ok()?;
+
+    encoded.split('-').map(|i| i.parse().ok()).collect()
+}
+
+fn mac(secret: &str, data: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac
+}
+
+fn signed_payload(poll_id: Uuid, encoded_order: &str) -> String {
+    format!("order:{}:{}", poll_id, encoded_order)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "test-secret";
+
+    #[test]
+    fn order_is_a_stable_permutation() {
+        let poll_id = Uuid::new_v4();
+        let order = shuffled_order(SECRET, poll_id, "voter", 6);
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..6).collect::<Vec<_>>());
+        assert_eq!(shuffled_order(SECRET, poll_id, "voter", 6), order);
+    }
+
+    #[test]
+    fn signed_order_round_trips() {
+        let poll_id = Uuid::new_v4();
+        let token = sign_order(SECRET, poll_id, &[2, 0, 1]);
+        assert!(token.starts_with("2-0-1."));
+        assert_eq!(verify_order(SECRET, poll_id, &token), Some(vec![2, 0, 1]));
+    }
+
+    #[test]
+    fn tampered_order_is_rejected() {
+        let poll_id = Uuid::new_v4();
+        let token = sign_order(SECRET, poll_id, &[2, 0, 1]);
+        let (_, signature) = token.split_once('.').unwrap();
+        assert_eq!(verify_order(SECRET, poll_id, &format!("0-1-2.{}", signature)), None);
+    }
+
+    #[test]
+    fn token_is_bound_to_poll_and_secret() {
+        let poll_id = Uuid::new_v4();
+        let token = sign_order(SECRET, poll_id, &[1, 0]);
+        assert_eq!(verify_order(SECRET, Uuid::new_v4(), &token), None);
+        assert_eq!(verify_order("other-secret", poll_id, &token), None);
+    }
+
+    #[test]
+    fn malformed_tokens_are_rejected() {
+        let poll_id = Uuid::new_v4();
+        assert_eq!(verify_order(SECRET, poll_id, "1-0"), None);
+        assert_eq!(verify_order(SECRET, poll_id, "1-0.abc"), None);
+        assert_eq!(verify_order(SECRET, poll_id, "1-0.zz"), None);
+    }
+
+    #[test]
+    fn decodes_hex() {
+        assert_eq!(decode_hex("00ff10"), Some(vec![0x00, 0xff, 0x10]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("gg"), None);
+    }
+}
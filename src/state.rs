@@ -0,0 +1,23 @@
+// src/state.rs
+use axum::extract::FromRef;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::binance::BinanceClient;
+use crate::config::Config;
+use crate::features::FeatureFlags;
+use crate::notify::Notifier;
+use crate::price_stream::PriceStream;
+use crate::rate_limit::RateLimits;
+
+/// Shared state handed to every handler.
+#[derive(Clone, FromRef)]
+pub struct AppState {
+    pub pool: PgPool,
+    pub config: Arc<Config>,
+    pub notifier: Option<Arc<dyn Notifier>>,
+    pub binance: BinanceClient,
+    pub prices: PriceStream,
+    pub rate_limits: Arc<RateLimits>,
+    pub features: FeatureFlags,
+}
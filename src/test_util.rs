@@ -0,0 +1,113 @@
+// src/test_util.rs
+use axum::{
+    body::{self, Body},
+    http::{header, HeaderMap, Method, Request, StatusCode},
+    Router,
+};
+use serde_json::Value;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use crate::binance::BinanceClient;
+use crate::config::Config;
+use crate::features::FeatureFlags;
+use crate::price_stream::PriceStream;
+use crate::rate_limit::RateLimits;
+use crate::routes;
+use crate::state::AppState;
+
+/// `ADMIN_TOKEN` of the test configuration.
+pub const ADMIN_TOKEN: &str = "test-admin-token";
+
+/// Address votes come from unless a test picks another.
+pub const VOTER_IP: &str = "203.0.113.7";
+
+/// Configuration from the defaults and `vars`, ignoring the process
+/// environment. Rate limits are off unless `vars` turn them on.
+pub fn config(vars: &[(&str, &str)]) -> Config {
+    let defaults = [
+        ("VOTER_IP_SALT", "test-salt"),
+        ("ADMIN_TOKEN", ADMIN_TOKEN),
+        ("VOTE_RATE_LIMIT_RPS", "0"),
+        ("POLL_CREATE_RATE_LIMIT_RPS", "0"),
+    ];
+    let vars: HashMap<String, String> = defaults
+        .iter()
+        .chain(vars)
+        .map(|&(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    Config::from_vars(|key| vars.get(key).cloned())
+}
+
+/// State backed by `pool`; Binance points at a closed port.
+pub fn state(pool: PgPool, config: Config) -> AppState {
+    let rate_limits = Arc::new(RateLimits::new(config.vote_rate_limit, config.poll_create_rate_limit));
+    AppState {
+        pool,
+        prices: PriceStream::new(config.price_stream_buffer),
+        features: FeatureFlags::new(&config.disabled_features),
+        config: Arc::new(config),
+        notifier: None,
+        binance: BinanceClient::new("http://127.0.0.1:9".to_string()),
+        rate_limits,
+    }
+}
+
+/// Pool that never connects, for requests answered before any query.
+pub fn lazy_pool() -> PgPool {
+    PgPoolOptions::new().connect_lazy("postgres://localhost/unused").unwrap()
+}
+
+/// The API router over `state`, as `main` serves it minus the outer layers.
+pub fn app(state: AppState) -> Router {
+    routes::create_router().with_state(state)
+}
+
+/// Request from `VOTER_IP`, with `body` as JSON unless it is `Value::Null`.
+pub fn request(method: Method, uri: &str, body: Value) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("x-forwarded-for", VOTER_IP)
+        .header(header::USER_AGENT, "poll-tests");
+    if body.is_null() {
+        return builder.body(Body::empty()).unwrap();
+    }
+    builder
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+/// `request` carrying the admin bearer token.
+pub fn admin_request(method: Method, uri: &str, body: Value) -> Request<Body> {
+    let mut request = request(method, uri, body);
+    let token = format!("Bearer {}", ADMIN_TOKEN).parse().unwrap();
+    request.headers_mut().insert(header::AUTHORIZATION, token);
+    request
+}
+
+/// Response of `app` to `request`: status, headers and raw body.
+pub async fn send_raw(app: &Router, request: Request<Body>) -> (StatusCode, HeaderMap, Vec<u8>) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let (parts, body) = response.into_parts();
+    let bytes = body::to_bytes(body, usize::MAX).await.unwrap();
+    (parts.status, parts.headers, bytes.to_vec())
+}
+
+/// Response of `app` to `request`, with the body parsed as JSON (`Value::Null` when empty).
+pub async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let (status, _, body) = send_raw(app, request).await;
+    let json = if body.is_empty() { Value::Null } else { serde_json::from_slice(&body).unwrap() };
+    (status, json)
+}
+
+/// Creates a poll through the API and returns its id.
+pub async fn create_poll(app: &Router, body: Value) -> Uuid {
+    let (status, poll) = send(app, admin_request(Method::POST, "/api/polls", body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", poll);
+    poll["id"].as_str().unwrap().parse().unwrap()
+}